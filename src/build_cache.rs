@@ -0,0 +1,243 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub use crate::fileio::MyFileIO;
+
+pub struct BuildCache {}
+
+impl BuildCache {
+    /**
+     * ソースファイル・コンパイラ・フラグ・ローカルヘッダからキャッシュキーを計算
+     * (Rustのバージョンによって変わらないようFNV-1aでハッシュ化する)
+     * @param cpp_path C++ファイルへのパス
+     * @param include_dir `-I` で指定するディレクトリへのパス
+     * @param compiler コンパイラのパス
+     * @param flags コンパイルフラグ
+     * @return 異常終了: エラー
+     *         正常終了: キャッシュキー(16進数文字列)
+     */
+    pub fn compute_key(
+        cpp_path: &Path,
+        include_dir: &Path,
+        compiler: &str,
+        flags: &[String],
+    ) -> Result<String> {
+        let mut hasher = Fnv1a::new();
+        hasher.write_field(compiler.as_bytes());
+
+        /* コンパイラが更新された場合も再コンパイルするよう，実体のパスと更新日時を含める */
+        if let Some(compiler_path) = BuildCache::resolve_compiler(compiler) {
+            hasher.write_field(compiler_path.to_string_lossy().as_bytes());
+            let modified = fs::metadata(&compiler_path)?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            hasher.write_field(&modified.as_nanos().to_le_bytes());
+        }
+        hasher.write_field(&(flags.len() as u64).to_le_bytes());
+        for flag in flags {
+            hasher.write_field(flag.as_bytes());
+        }
+        hasher.write_field(&fs::read(cpp_path)?);
+
+        /* インクルードしているローカルヘッダの内容もキーに含める */
+        for header in BuildCache::collect_local_headers(cpp_path, include_dir) {
+            hasher.write_field(header.to_string_lossy().as_bytes());
+            hasher.write_field(&fs::read(&header)?);
+        }
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    /**
+     * コンパイラの実体のパスを求める (`/` を含まない場合はPATHから探し，シンボリックリンクを辿る)
+     * @param compiler コンパイラのパスまたはコマンド名
+     * @return 実体のパス (見つからない場合はNone)
+     */
+    fn resolve_compiler(compiler: &str) -> Option<PathBuf> {
+        let path = if compiler.contains('/') {
+            PathBuf::from(compiler)
+        } else {
+            env::split_paths(&env::var_os("PATH")?)
+                .map(|dir| dir.join(compiler))
+                .find(|candidate| candidate.is_file())?
+        };
+        fs::canonicalize(path).ok()
+    }
+
+    /**
+     * キャッシュが有効かどうかを判定
+     * @param bin_path 実行形式ファイルへのパス
+     * @param key キャッシュキー
+     * @return 実行形式ファイルが存在し，キーが一致すればtrue
+     */
+    pub fn is_fresh(bin_path: &Path, key: &str) -> bool {
+        if !bin_path.exists() {
+            return false;
+        }
+        match MyFileIO::read_file(String::from(
+            BuildCache::key_path(bin_path).to_str().unwrap(),
+        )) {
+            Ok(stored_key) => stored_key.trim() == key,
+            Err(_) => false,
+        }
+    }
+
+    /**
+     * キャッシュキーを保存
+     * @param bin_path 実行形式ファイルへのパス
+     * @param key キャッシュキー
+     * @return 正常終了の有無
+     */
+    pub fn store(bin_path: &Path, key: &str) -> Result<()> {
+        MyFileIO::write_file(&BuildCache::key_path(bin_path), &String::from(key))
+    }
+
+    /**
+     * キャッシュキーの保存先 (smart.out に対して smart.hash)
+     * @param bin_path 実行形式ファイルへのパス
+     * @return キャッシュキーの保存先
+     */
+    fn key_path(bin_path: &Path) -> PathBuf {
+        bin_path.with_extension("hash")
+    }
+
+    /**
+     * `#include "..."` で読み込んでいるローカルヘッダを再帰的に列挙
+     * @param cpp_path C++ファイルへのパス
+     * @param include_dir `-I` で指定するディレクトリへのパス
+     * @return ヘッダファイルへのパスの集合
+     */
+    fn collect_local_headers(cpp_path: &Path, include_dir: &Path) -> BTreeSet<PathBuf> {
+        let mut headers = BTreeSet::new();
+        let mut stack = vec![cpp_path.to_path_buf()];
+        while let Some(path) = stack.pop() {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let current_dir = path.parent().unwrap_or(include_dir).to_path_buf();
+            for name in BuildCache::parse_local_includes(&content) {
+                /* インクルード元と同じディレクトリ，`-I` のディレクトリの順に探す */
+                let found = [current_dir.join(&name), include_dir.join(&name)]
+                    .iter()
+                    .find(|candidate| candidate.is_file())
+                    .cloned();
+                if let Some(header) = found {
+                    if headers.insert(header.clone()) {
+                        stack.push(header);
+                    }
+                }
+            }
+        }
+        headers
+    }
+
+    /**
+     * ソースコードから `#include "..."` のファイル名を抽出
+     * @param content ソースコード
+     * @return ファイル名の一覧
+     */
+    fn parse_local_includes(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let rest = line.trim_start().strip_prefix('#')?;
+                let rest = rest.trim_start().strip_prefix("include")?;
+                let rest = rest.trim_start().strip_prefix('"')?;
+                let end = rest.find('"')?;
+                Some(String::from(&rest[..end]))
+            })
+            .collect()
+    }
+}
+
+/**
+ * 64bitのFNV-1aハッシュ
+ */
+struct Fnv1a {
+    hash: u64,
+}
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Fnv1a {
+        Fnv1a {
+            hash: Fnv1a::OFFSET_BASIS,
+        }
+    }
+
+    /**
+     * バイト列を追加
+     * @param bytes 追加するバイト列
+     */
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(Fnv1a::PRIME);
+        }
+    }
+
+    /**
+     * 区切りが曖昧にならないよう長さを前置してバイト列を追加
+     * @param bytes 追加するバイト列
+     */
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * ローカルヘッダの抽出テスト
+     */
+    fn parse_local_includes_test() {
+        let content = "#include <iostream>\n#include \"lib/a.hpp\"\n  #  include \"b.h\" // comment\nint main() {}\n";
+        assert_eq!(
+            BuildCache::parse_local_includes(content),
+            vec![String::from("lib/a.hpp"), String::from("b.h")]
+        );
+    }
+
+    #[test]
+    /**
+     * キャッシュキーがRustのバージョンに依存しない値になるかのテスト
+     */
+    fn compute_key_test() {
+        let mut hasher = Fnv1a::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let dir = env::temp_dir().join(format!("cpstt_build_cache_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cpp_path = dir.join("smart.cpp");
+        fs::write(&cpp_path, "int main() {}\n").unwrap();
+        let flags = vec![String::from("-O2")];
+        let key = BuildCache::compute_key(&cpp_path, &dir, "/nonexistent/g++", &flags).unwrap();
+        assert_eq!(key, "e2718ae9aad7a75e");
+        let other_flags = vec![String::from("-O0")];
+        let other = BuildCache::compute_key(&cpp_path, &dir, "/nonexistent/g++", &other_flags);
+        assert_ne!(other.unwrap(), key);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(BuildCache::resolve_compiler("sh").unwrap().is_absolute());
+        assert_eq!(
+            BuildCache::resolve_compiler("cpstt_nonexistent_compiler"),
+            None
+        );
+    }
+}
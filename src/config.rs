@@ -0,0 +1,838 @@
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+use toml::Value;
+
+pub use crate::fileio::{MyFileIO, SettingsClass, SettingsOverride};
+pub use crate::print_console::PrintError;
+
+/* 環境変数で設定を上書きする場合の接頭辞 (例: CPSTT_EXECUTION_TIME_LIMIT) */
+const ENV_PREFIX: &str = "CPSTT_";
+/* 環境変数で上書きできるセクション */
+const ENV_SECTIONS: [&str; 4] = ["execution", "logging", "compile", "judge"];
+
+/* 設定ファイルで使える項目名 (language.<拡張子> は任意の拡張子を使える) */
+const SECTIONS: [&str; 5] = ["execution", "logging", "compile", "language", "judge"];
+const EXECUTION_KEYS: [&str; 7] = [
+    "max_output_len",
+    "max_output_line",
+    "time_limit",
+    "memory_limit",
+    "time_measure",
+    "jobs",
+    "bin_extension",
+];
+const LOGGING_KEYS: [&str; 3] = ["dump_exe_result", "show_diff", "save_diff"];
+const COMPILE_KEYS: [&str; 7] = [
+    "compiler",
+    "std",
+    "optimization",
+    "flags",
+    "generator",
+    "smart",
+    "stupid",
+];
+const COMPILE_OVERRIDE_KEYS: [&str; 4] = ["compiler", "std", "optimization", "flags"];
+const LANGUAGE_KEYS: [&str; 2] = ["compile", "run"];
+const JUDGE_KEYS: [&str; 3] = ["compare", "abs_eps", "rel_eps"];
+
+/* memory_limit(MB)の上限 (1TB) */
+const MAX_MEMORY_LIMIT: u64 = 1 << 20;
+
+/**
+ * 設定の値をどこから読み込んだか
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    /* 既定値 */
+    Default,
+    /* ユーザ全体の設定 (~/.config/cpstt/settings.toml) */
+    Global(PathBuf),
+    /* テストディレクトリの設定 (settings.toml) */
+    Project(PathBuf),
+    /* 環境変数 */
+    Env(String),
+    /* コマンドライン引数 */
+    Cli(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::Global(path) | ConfigSource::Project(path) => {
+                write!(f, "{}", path.to_string_lossy())
+            }
+            ConfigSource::Env(name) => write!(f, "env {}", name),
+            ConfigSource::Cli(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+/**
+ * 設定の問題点
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    /* 問題のある値の読み込み元 */
+    pub source: ConfigSource,
+    /* 1始まりの(行, 列) (設定ファイル以外や位置が分からない場合はNone) */
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn new(source: ConfigSource, position: Option<(usize, usize)>, message: String) -> Self {
+        ConfigProblem {
+            source,
+            position,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => {
+                write!(f, "{}:{}:{}: {}", self.source, line, column, self.message)
+            }
+            None => write!(f, "{}: {}", self.source, self.message),
+        }
+    }
+}
+
+/**
+ * 読み込んだ設定と，各項目の読み込み元
+ */
+#[derive(Debug)]
+pub struct LoadedConfig {
+    pub settings: SettingsClass,
+    /* 全ての層を重ねた設定の値 */
+    pub value: Value,
+    /* 項目 (例: "execution.time_limit") ごとの読み込み元 */
+    pub sources: BTreeMap<String, ConfigSource>,
+}
+
+pub struct Config {}
+
+impl Config {
+    /**
+     * 既定値 < ユーザ全体の設定 < テストディレクトリの設定 < 環境変数 < コマンドライン引数 の順に重ねて設定を読み込む
+     * @param root_path テストディレクトリへの絶対パス
+     * @param settings_override コマンドライン引数による上書き
+     * @return 異常終了: 設定の問題点の一覧
+     *         正常終了: 読み込んだ設定
+     */
+    pub fn load(
+        root_path: &Path,
+        settings_override: &SettingsOverride,
+    ) -> Result<LoadedConfig, Vec<ConfigProblem>> {
+        let default = Value::try_from(SettingsClass::default()).map_err(|e| {
+            vec![ConfigProblem::new(
+                ConfigSource::Default,
+                None,
+                e.to_string(),
+            )]
+        })?;
+        let mut layers = vec![(ConfigSource::Default, default)];
+        let mut problems = Vec::new();
+
+        /* 設定ファイルは1つずつ確認し，問題のあるファイルは重ねない */
+        let mut contents = HashMap::new();
+        let config_files = [
+            Config::global_path().map(ConfigSource::Global),
+            Some(ConfigSource::Project(root_path.join("settings.toml"))),
+        ];
+        for source in config_files.iter().flatten() {
+            let path = match source {
+                ConfigSource::Global(path) | ConfigSource::Project(path) => path,
+                _ => continue,
+            };
+            if !path.is_file() {
+                continue;
+            }
+            let content = match MyFileIO::read_file(String::from(path.to_str().unwrap())) {
+                Ok(content) => content,
+                Err(e) => {
+                    problems.push(ConfigProblem::new(source.clone(), None, e.to_string()));
+                    continue;
+                }
+            };
+            let (value, file_problems) = Config::check_file(source, &content);
+            problems.extend(file_problems);
+            if let Some(value) = value {
+                layers.push((source.clone(), value));
+            }
+            contents.insert(path.clone(), content);
+        }
+        for (name, value) in Config::env_layers(env::vars()) {
+            let source = ConfigSource::Env(name);
+            let env_problems = Config::check_value(&source, &value, None);
+            if env_problems.is_empty() {
+                layers.push((source, value));
+            }
+            problems.extend(env_problems);
+        }
+        for (flag, value) in Config::cli_layers(settings_override) {
+            layers.push((ConfigSource::Cli(flag), value));
+        }
+
+        let mut merged = Value::Table(Table::new());
+        let mut sources = BTreeMap::new();
+        for (source, value) in layers {
+            Config::merge(&mut merged, value, "", &source, &mut sources);
+        }
+        let settings: SettingsClass = match merged.clone().try_into() {
+            Ok(settings) => settings,
+            Err(e) => {
+                problems.push(ConfigProblem::new(
+                    ConfigSource::Default,
+                    None,
+                    e.to_string(),
+                ));
+                return Err(problems);
+            }
+        };
+        problems.extend(Config::check_settings(&settings, &sources, &contents));
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+        Ok(LoadedConfig {
+            settings,
+            value: merged,
+            sources,
+        })
+    }
+
+    /**
+     * 設定を読み込み，問題があれば全て表示する
+     * @param root_path テストディレクトリへの絶対パス
+     * @param settings_override コマンドライン引数による上書き
+     * @return 異常終了: エラー
+     *         正常終了: 読み込んだ設定
+     */
+    pub fn load_or_report(
+        root_path: &Path,
+        settings_override: &SettingsOverride,
+    ) -> Result<LoadedConfig> {
+        match Config::load(root_path, settings_override) {
+            Ok(loaded) => Ok(loaded),
+            Err(problems) => {
+                for problem in &problems {
+                    PrintError::print_error(problem.to_string());
+                }
+                bail!("Some Error is occurred!");
+            }
+        }
+    }
+
+    /**
+     * 設定ファイルの文法・項目名・型を確認
+     * @param source 設定ファイル
+     * @param content 設定ファイルの内容
+     * @return (問題が無ければ設定の値, 問題点の一覧)
+     */
+    fn check_file(source: &ConfigSource, content: &str) -> (Option<Value>, Vec<ConfigProblem>) {
+        let value = match content.parse::<Value>() {
+            Ok(value) => value,
+            Err(e) => {
+                let position = e.line_col().map(|(line, column)| (line + 1, column + 1));
+                let problem =
+                    ConfigProblem::new(source.clone(), position, Config::strip_position(&e));
+                return (None, vec![problem]);
+            }
+        };
+        let problems = Config::check_value(source, &value, Some(content));
+        if problems.is_empty() {
+            (Some(value), problems)
+        } else {
+            (None, problems)
+        }
+    }
+
+    /**
+     * 設定の値に未知の項目や型の誤りが無いか確認
+     * @param source 値の読み込み元
+     * @param value 設定の値
+     * @param content 設定ファイルの内容 (設定ファイル以外の場合はNone)
+     * @return 問題点の一覧
+     */
+    fn check_value(
+        source: &ConfigSource,
+        value: &Value,
+        content: Option<&str>,
+    ) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        let mut unknown_keys = Vec::new();
+        Config::find_unknown_keys(value, "", &mut unknown_keys);
+        for (key_path, suggestion) in unknown_keys {
+            let mut message = format!("unknown key `{}`", key_path);
+            if let Some(suggestion) = suggestion {
+                message += &format!(", did you mean `{}`?", suggestion);
+            }
+            let position = content.and_then(|content| Config::find_position(content, &key_path));
+            problems.push(ConfigProblem::new(source.clone(), position, message));
+        }
+        if !problems.is_empty() {
+            return problems;
+        }
+
+        /* 型の誤りは設定ファイルから直接読み込むと位置が分かる */
+        let type_error = match content {
+            Some(content) => toml::from_str::<SettingsClass>(content).err().map(|e| {
+                let position = e.line_col().map(|(line, column)| (line + 1, column + 1));
+                (position, Config::strip_position(&e))
+            }),
+            None => value
+                .clone()
+                .try_into::<SettingsClass>()
+                .err()
+                .map(|e| (None, e.to_string())),
+        };
+        if let Some((position, message)) = type_error {
+            problems.push(ConfigProblem::new(source.clone(), position, message));
+        }
+        problems
+    }
+
+    /**
+     * 未知の項目を探す
+     * @param value 設定の値
+     * @param path 値の位置
+     * @param unknown_keys (未知の項目の位置, 最も近い既知の項目名) の一覧
+     */
+    fn find_unknown_keys(
+        value: &Value,
+        path: &str,
+        unknown_keys: &mut Vec<(String, Option<String>)>,
+    ) {
+        let table = match value.as_table() {
+            Some(table) => table,
+            None => return,
+        };
+        let known_keys = Config::known_keys(path);
+        for (key, value) in table {
+            let key_path = Config::join_path(path, key);
+            if let Some(known_keys) = known_keys {
+                if !known_keys.contains(&key.as_str()) {
+                    let suggestion = Config::closest_key(key, known_keys).map(String::from);
+                    unknown_keys.push((key_path, suggestion));
+                    continue;
+                }
+            }
+            Config::find_unknown_keys(value, &key_path, unknown_keys);
+        }
+    }
+
+    /**
+     * テーブルで使える項目名
+     * @param path テーブルの位置
+     * @return 項目名の一覧 (任意の項目名を使える場合はNone)
+     */
+    fn known_keys(path: &str) -> Option<&'static [&'static str]> {
+        match path {
+            "" => Some(&SECTIONS),
+            "execution" => Some(&EXECUTION_KEYS),
+            "logging" => Some(&LOGGING_KEYS),
+            "compile" => Some(&COMPILE_KEYS),
+            "compile.generator" | "compile.smart" | "compile.stupid" => {
+                Some(&COMPILE_OVERRIDE_KEYS)
+            }
+            "judge" => Some(&JUDGE_KEYS),
+            _ if path.starts_with("language.") => Some(&LANGUAGE_KEYS),
+            _ => None,
+        }
+    }
+
+    /**
+     * 編集距離が最も近い項目名を探す
+     * @param key 未知の項目名
+     * @param known_keys 既知の項目名
+     * @return 最も近い項目名 (十分に近いものが無い場合はNone)
+     */
+    fn closest_key(key: &str, known_keys: &[&'static str]) -> Option<&'static str> {
+        let max_distance = (key.chars().count() / 3).max(2);
+        known_keys
+            .iter()
+            .map(|known_key| (Config::edit_distance(key, known_key), *known_key))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, known_key)| known_key)
+    }
+
+    /**
+     * 2つの文字列の編集距離 (レーベンシュタイン距離)
+     * @param a 文字列
+     * @param b 文字列
+     * @return 編集距離
+     */
+    fn edit_distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut dp: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut prev = dp[0];
+            dp[0] = i + 1;
+            for j in 0..b.len() {
+                let current = dp[j + 1];
+                dp[j + 1] = if ca == b[j] {
+                    prev
+                } else {
+                    prev.min(dp[j]).min(dp[j + 1]) + 1
+                };
+                prev = current;
+            }
+        }
+        dp[b.len()]
+    }
+
+    /**
+     * 重ねた設定の値が妥当か確認
+     * @param settings 重ねた設定
+     * @param sources 項目ごとの読み込み元
+     * @param contents 設定ファイルごとの内容
+     * @return 問題点の一覧
+     */
+    fn check_settings(
+        settings: &SettingsClass,
+        sources: &BTreeMap<String, ConfigSource>,
+        contents: &HashMap<PathBuf, String>,
+    ) -> Vec<ConfigProblem> {
+        let execution = &settings.execution;
+        let judge = &settings.judge;
+        let mut checks = vec![
+            (
+                String::from("execution.max_output_len"),
+                execution.max_output_len >= 0,
+                "must be 0 or greater",
+            ),
+            (
+                String::from("execution.max_output_line"),
+                execution.max_output_line >= 1,
+                "must be 1 or greater",
+            ),
+            (
+                String::from("execution.time_limit"),
+                execution.time_limit > 0,
+                "must be greater than 0",
+            ),
+            (
+                String::from("execution.memory_limit"),
+                execution.memory_limit <= MAX_MEMORY_LIMIT,
+                "must be 1048576 (1 TB) or less (0 means unlimited)",
+            ),
+            (
+                String::from("execution.jobs"),
+                execution.jobs != Some(0),
+                "must be greater than 0",
+            ),
+            (
+                String::from("execution.bin_extension"),
+                !execution.bin_extension.is_empty() && !execution.bin_extension.contains('.'),
+                "must be a non-empty extension without `.`",
+            ),
+            (
+                String::from("compile.compiler"),
+                !settings.compile.compiler.is_empty(),
+                "must not be empty",
+            ),
+            (
+                String::from("judge.abs_eps"),
+                judge.abs_eps.is_finite() && judge.abs_eps >= 0.0,
+                "must be 0 or greater",
+            ),
+            (
+                String::from("judge.rel_eps"),
+                judge.rel_eps.is_finite() && judge.rel_eps >= 0.0,
+                "must be 0 or greater",
+            ),
+        ];
+        for (extension, language) in &settings.language {
+            checks.push((
+                format!("language.{}.run", extension),
+                !language.run.trim().is_empty(),
+                "must not be empty",
+            ));
+        }
+
+        let mut problems = Vec::new();
+        for (key_path, is_valid, requirement) in checks {
+            if is_valid {
+                continue;
+            }
+            let source = sources
+                .get(&key_path)
+                .cloned()
+                .unwrap_or(ConfigSource::Default);
+            let position = match &source {
+                ConfigSource::Global(path) | ConfigSource::Project(path) => contents
+                    .get(path)
+                    .and_then(|content| Config::find_position(content, &key_path)),
+                _ => None,
+            };
+            let value = Config::get(settings, &key_path);
+            problems.push(ConfigProblem::new(
+                source,
+                position,
+                format!("`{}` {} (found {})", key_path, requirement, value),
+            ));
+        }
+        problems
+    }
+
+    /**
+     * 設定の項目の値を文字列で取得
+     * @param settings 設定
+     * @param key_path 項目の位置
+     * @return 値 (TOML形式)
+     */
+    fn get(settings: &SettingsClass, key_path: &str) -> String {
+        let value = match Value::try_from(settings) {
+            Ok(value) => value,
+            Err(_) => return String::new(),
+        };
+        key_path
+            .split('.')
+            .try_fold(&value, |value, key| value.get(key))
+            .map_or_else(String::new, |value| value.to_string())
+    }
+
+    /**
+     * 設定ファイルから項目が書かれている位置を探す
+     * @param content 設定ファイルの内容
+     * @param key_path 項目の位置 (例: "execution.time_limit")
+     * @return 1始まりの(行, 列) (見つからない場合はNone)
+     */
+    fn find_position(content: &str, key_path: &str) -> Option<(usize, usize)> {
+        let normalize = |key: &str| -> String {
+            key.split('.')
+                .map(|part| part.trim().trim_matches('"').trim_matches('\''))
+                .collect::<Vec<&str>>()
+                .join(".")
+        };
+        let mut table = String::new();
+        for (line_index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let column = line.len() - trimmed.len() + 1;
+            if trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = trimmed.strip_prefix('[') {
+                let header = header.trim_start_matches('[');
+                let header = header.split(']').next().unwrap_or("");
+                table = normalize(header);
+                if table == key_path {
+                    return Some((line_index + 1, column));
+                }
+                continue;
+            }
+            if let Some((key, _)) = trimmed.split_once('=') {
+                if Config::join_path(&table, &normalize(key)) == key_path {
+                    return Some((line_index + 1, column));
+                }
+            }
+        }
+        None
+    }
+
+    /**
+     * TOMLのエラーメッセージから位置の部分 (" at line 1 column 2") を取り除く
+     * @param error TOMLのエラー
+     * @return エラーメッセージ
+     */
+    fn strip_position(error: &toml::de::Error) -> String {
+        let message = error.to_string();
+        match message.rfind(" at line ") {
+            Some(index) => String::from(&message[..index]),
+            None => message,
+        }
+    }
+
+    /**
+     * ユーザ全体の設定ファイルへのパス ($XDG_CONFIG_HOME/cpstt/settings.toml または ~/.config/cpstt/settings.toml)
+     * @return 設定ファイルへのパス (ホームディレクトリが分からない場合はNone)
+     */
+    pub fn global_path() -> Option<PathBuf> {
+        let config_dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_dir.join("cpstt/settings.toml"))
+    }
+
+    /**
+     * CPSTT_<セクション>_<項目> の環境変数を設定の値にする
+     * @param vars 環境変数の一覧
+     * @return (環境変数名, 設定の値) の一覧
+     */
+    fn env_layers(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, Value)> {
+        let mut layers: Vec<(String, Value)> = vars
+            .filter_map(|(name, raw)| {
+                let rest = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+                let (section, key) = rest.split_once('_')?;
+                if !ENV_SECTIONS.contains(&section) || key.is_empty() {
+                    return None;
+                }
+                Some((
+                    name.clone(),
+                    Config::nested(&[section, key], Config::parse_env(&raw)),
+                ))
+            })
+            .collect();
+        /* 同じ項目を指定した場合の結果が環境変数の順番に依らないようにする */
+        layers.sort_by(|a, b| a.0.cmp(&b.0));
+        layers
+    }
+
+    /**
+     * 環境変数の値をTOMLの値として解釈する (数値・真偽値・配列として読めなければ文字列)
+     * @param raw 環境変数の値
+     * @return 設定の値
+     */
+    fn parse_env(raw: &str) -> Value {
+        format!("value = {}", raw)
+            .parse::<Value>()
+            .ok()
+            .and_then(|table| table.get("value").cloned())
+            .unwrap_or_else(|| Value::String(String::from(raw)))
+    }
+
+    /**
+     * コマンドライン引数による上書きを設定の値にする
+     * @param settings_override コマンドライン引数による上書き
+     * @return (オプション名, 設定の値) の一覧
+     */
+    fn cli_layers(settings_override: &SettingsOverride) -> Vec<(String, Value)> {
+        let mut layers = Vec::new();
+        if let Some(time_limit) = settings_override.time_limit {
+            layers.push((
+                String::from("--time-limit"),
+                Config::nested(
+                    &["execution", "time_limit"],
+                    Value::Integer(time_limit as i64),
+                ),
+            ));
+        }
+        if let Some(jobs) = settings_override.jobs {
+            layers.push((
+                String::from("-j, --jobs"),
+                Config::nested(&["execution", "jobs"], Value::Integer(jobs as i64)),
+            ));
+        }
+        layers
+    }
+
+    /**
+     * 1つの項目だけを持つ入れ子のテーブルを作成
+     * @param keys セクションと項目名
+     * @param value 項目の値
+     * @return 設定の値
+     */
+    fn nested(keys: &[&str], value: Value) -> Value {
+        keys.iter().rev().fold(value, |value, key| {
+            let mut table = Table::new();
+            table.insert(String::from(*key), value);
+            Value::Table(table)
+        })
+    }
+
+    /**
+     * 設定の値を重ねる (テーブルは項目ごとに，それ以外の値は丸ごと上書きする)
+     * @param base 重ねられる値
+     * @param layer 上に重ねる値
+     * @param path baseの位置 (例: "execution")
+     * @param source layerの読み込み元
+     * @param sources 項目ごとの読み込み元
+     */
+    fn merge(
+        base: &mut Value,
+        layer: Value,
+        path: &str,
+        source: &ConfigSource,
+        sources: &mut BTreeMap<String, ConfigSource>,
+    ) {
+        match (base, layer) {
+            (Value::Table(base), Value::Table(layer)) => {
+                for (key, value) in layer {
+                    let key_path = Config::join_path(path, &key);
+                    match base.get_mut(&key) {
+                        Some(base_value) => {
+                            Config::merge(base_value, value, &key_path, source, sources)
+                        }
+                        None => {
+                            Config::record_sources(&value, &key_path, source, sources);
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (base, layer) => {
+                Config::record_sources(&layer, path, source, sources);
+                *base = layer;
+            }
+        }
+    }
+
+    /**
+     * 値に含まれる全ての項目の読み込み元を記録
+     * @param value 設定の値
+     * @param path 値の位置
+     * @param source 読み込み元
+     * @param sources 項目ごとの読み込み元
+     */
+    fn record_sources(
+        value: &Value,
+        path: &str,
+        source: &ConfigSource,
+        sources: &mut BTreeMap<String, ConfigSource>,
+    ) {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    let key_path = Config::join_path(path, key);
+                    Config::record_sources(value, &key_path, source, sources);
+                }
+            }
+            _ => {
+                sources.insert(String::from(path), source.clone());
+            }
+        }
+    }
+
+    /**
+     * 項目の位置を連結
+     * @param path 親の位置 (最上位の場合は空文字列)
+     * @param key 項目名
+     * @return 項目の位置 (例: "execution.time_limit")
+     */
+    fn join_path(path: &str, key: &str) -> String {
+        if path.is_empty() {
+            String::from(key)
+        } else {
+            format!("{}.{}", path, key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * 設定の値の重ね合わせと読み込み元の記録テスト
+     */
+    fn merge_test() {
+        let mut merged = Value::Table(Table::new());
+        let mut sources = BTreeMap::new();
+        let default = Value::try_from(SettingsClass::default()).unwrap();
+        Config::merge(
+            &mut merged,
+            default,
+            "",
+            &ConfigSource::Default,
+            &mut sources,
+        );
+        let project = ConfigSource::Project(PathBuf::from("settings.toml"));
+        let layer = "[execution]\ntime_limit = 1000\n[language.py]\nrun = \"python3 {source}\"\n"
+            .parse::<Value>()
+            .unwrap();
+        Config::merge(&mut merged, layer, "", &project, &mut sources);
+        let env_layers = Config::env_layers(
+            vec![
+                (
+                    String::from("CPSTT_EXECUTION_TIME_LIMIT"),
+                    String::from("3000"),
+                ),
+                (String::from("CPSTT_JUDGE_COMPARE"), String::from("tokens")),
+                (String::from("CPSTT_UNKNOWN_KEY"), String::from("1")),
+                (String::from("PATH"), String::from("/bin")),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(env_layers.len(), 2);
+        for (name, value) in env_layers {
+            Config::merge(
+                &mut merged,
+                value,
+                "",
+                &ConfigSource::Env(name),
+                &mut sources,
+            );
+        }
+
+        let settings: SettingsClass = merged.try_into().unwrap();
+        assert_eq!(settings.execution.time_limit, 3000);
+        assert_eq!(settings.execution.max_output_line, 4);
+        assert_eq!(settings.judge.compare, crate::fileio::CompareMode::Tokens);
+        assert_eq!(settings.language["py"].run, "python3 {source}");
+        assert_eq!(
+            sources["execution.time_limit"],
+            ConfigSource::Env(String::from("CPSTT_EXECUTION_TIME_LIMIT"))
+        );
+        assert_eq!(sources["language.py.run"], project);
+        assert_eq!(sources["execution.max_output_len"], ConfigSource::Default);
+    }
+
+    #[test]
+    /**
+     * 設定ファイルの項目名・型・値の確認テスト
+     */
+    fn check_file_test() {
+        let project = ConfigSource::Project(PathBuf::from("settings.toml"));
+
+        /* 未知の項目は位置と最も近い項目名を表示する */
+        let content = "[execution]\nmax_output_len = 200\n  time_limt = 1000\n";
+        let (value, problems) = Config::check_file(&project, content);
+        assert!(value.is_none());
+        assert_eq!(
+            problems[0].to_string(),
+            "settings.toml:3:3: unknown key `execution.time_limt`, did you mean `time_limit`?"
+        );
+        assert_eq!(
+            Config::closest_key("complier", &COMPILE_KEYS),
+            Some("compiler")
+        );
+        assert_eq!(Config::closest_key("timeout", &EXECUTION_KEYS), None);
+
+        /* 型の誤りと文法の誤り */
+        let (_, problems) = Config::check_file(&project, "[execution]\ntime_limit = \"2s\"\n");
+        assert_eq!(problems[0].position, Some((2, 14)));
+        let (_, problems) = Config::check_file(&project, "[execution\n");
+        assert_eq!(problems[0].position.map(|(line, _)| line), Some(1));
+
+        /* 値の範囲は重ねた後に確認し，読み込み元の位置を表示する */
+        let content = "[execution]\ntime_limit = 0\nmax_output_len = -1\nmemory_limit = 2000000\n";
+        let (value, problems) = Config::check_file(&project, content);
+        assert!(problems.is_empty());
+        let mut merged = Value::Table(Table::new());
+        let mut sources = BTreeMap::new();
+        let default = Value::try_from(SettingsClass::default()).unwrap();
+        Config::merge(
+            &mut merged,
+            default,
+            "",
+            &ConfigSource::Default,
+            &mut sources,
+        );
+        Config::merge(&mut merged, value.unwrap(), "", &project, &mut sources);
+        let settings: SettingsClass = merged.try_into().unwrap();
+        let mut contents = HashMap::new();
+        contents.insert(PathBuf::from("settings.toml"), String::from(content));
+        let problems: Vec<String> = Config::check_settings(&settings, &sources, &contents)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "settings.toml:3:1: `execution.max_output_len` must be 0 or greater (found -1)",
+                "settings.toml:2:1: `execution.time_limit` must be greater than 0 (found 0)",
+                "settings.toml:4:1: `execution.memory_limit` must be 1048576 (1 TB) or less (0 means unlimited) (found 2000000)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,236 @@
+pub use crate::print_console::PrintColorize;
+
+/* 差分計算に使うDPテーブルの最大サイズ (これを超える場合は差分のある範囲をまとめて置換として扱う) */
+const MAX_DP_SIZE: usize = 4_000_000;
+
+/* 1行の内容と，改行で終わっているか */
+type Line<'a> = (&'a str, bool);
+
+/**
+ * 差分の1行
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine<'a> {
+    /* 両方に存在する行 */
+    Equal(Line<'a>),
+    /* stupidのみに存在する行 */
+    Delete(Line<'a>),
+    /* smartのみに存在する行 */
+    Insert(Line<'a>),
+}
+
+pub struct TextDiff {}
+
+impl TextDiff {
+    /**
+     * 出力を行に分割 (末尾の改行の後の空の要素は行として扱わない)
+     * @param content 出力
+     * @return 行の一覧 (改行文字は含まない)
+     */
+    pub fn split_lines(content: &str) -> Vec<&str> {
+        content.split_terminator('\n').collect()
+    }
+
+    /**
+     * stupid(期待値)からsmartへのunified diffを作成
+     * @param stupid stupidの出力
+     * @param smart smartの出力
+     * @param context 差分の前後に表示する行数
+     * @return unified diffの各行 (色なし)
+     */
+    pub fn unified(stupid: &str, smart: &str, context: usize) -> Vec<String> {
+        let stupid_lines = TextDiff::lines_with_newline(stupid);
+        let smart_lines = TextDiff::lines_with_newline(smart);
+        let script = TextDiff::edit_script(&stupid_lines, &smart_lines);
+
+        let mut output = vec![String::from("--- stupid"), String::from("+++ smart")];
+        /* 差分のある位置をcontext行の余白付きでまとめてhunkにする */
+        let changed: Vec<usize> = script
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, DiffLine::Equal(_)))
+            .map(|(i, _)| i)
+            .collect();
+        let mut i = 0;
+        while i < changed.len() {
+            let begin = changed[i].saturating_sub(context);
+            let mut end = changed[i];
+            while i < changed.len() && changed[i] <= end + 2 * context + 1 {
+                end = changed[i];
+                i += 1;
+            }
+            let end = (end + context + 1).min(script.len());
+
+            /* hunkの開始行番号を数える */
+            let stupid_start = script[..begin]
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Insert(_)))
+                .count();
+            let smart_start = script[..begin]
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Delete(_)))
+                .count();
+            let hunk = &script[begin..end];
+            let stupid_len = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Insert(_)))
+                .count();
+            let smart_len = hunk
+                .iter()
+                .filter(|line| !matches!(line, DiffLine::Delete(_)))
+                .count();
+            output.push(format!(
+                "@@ -{},{} +{},{} @@",
+                stupid_start + 1,
+                stupid_len,
+                smart_start + 1,
+                smart_len
+            ));
+            for line in hunk {
+                let (mark, (text, has_newline)) = match line {
+                    DiffLine::Equal(line) => (' ', line),
+                    DiffLine::Delete(line) => ('-', line),
+                    DiffLine::Insert(line) => ('+', line),
+                };
+                output.push(format!("{}{}", mark, text));
+                if !has_newline {
+                    output.push(String::from("\\ No newline at end of file"));
+                }
+            }
+        }
+        output
+    }
+
+    /**
+     * 出力を行に分割し，各行が改行で終わっているかを付ける (末尾の改行の有無も差分として扱うため)
+     * @param content 出力
+     * @return 行の一覧
+     */
+    fn lines_with_newline(content: &str) -> Vec<Line<'_>> {
+        let lines = TextDiff::split_lines(content);
+        let len = lines.len();
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| (line, i + 1 < len || content.ends_with('\n')))
+            .collect()
+    }
+
+    /**
+     * unified diffの各行に色を付ける
+     * @param line unified diffの1行
+     * @return 色付きの行
+     */
+    pub fn colorize(line: &str) -> String {
+        if line.starts_with("---") || line.starts_with("+++") {
+            String::from(line)
+        } else if line.starts_with("@@") {
+            PrintColorize::print_cyan(String::from(line))
+        } else if line.starts_with('-') {
+            PrintColorize::print_red(String::from(line))
+        } else if line.starts_with('+') {
+            PrintColorize::print_green(String::from(line))
+        } else {
+            String::from(line)
+        }
+    }
+
+    /**
+     * 行単位の編集スクリプトを最長共通部分列から作成
+     * @param a 変更前の行
+     * @param b 変更後の行
+     * @return 編集スクリプト
+     */
+    fn edit_script<'a>(a: &[Line<'a>], b: &[Line<'a>]) -> Vec<DiffLine<'a>> {
+        /* 共通の先頭と末尾は先に取り除く */
+        let prefix = a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        let a_mid = &a[prefix..a.len() - suffix];
+        let b_mid = &b[prefix..b.len() - suffix];
+
+        let mut script: Vec<DiffLine> = a[..prefix].iter().map(|l| DiffLine::Equal(*l)).collect();
+        if (a_mid.len() + 1) * (b_mid.len() + 1) > MAX_DP_SIZE {
+            script.extend(a_mid.iter().map(|l| DiffLine::Delete(*l)));
+            script.extend(b_mid.iter().map(|l| DiffLine::Insert(*l)));
+        } else {
+            /* lcs[i][j] = a_mid[i..] と b_mid[j..] の最長共通部分列の長さ */
+            let (n, m) = (a_mid.len(), b_mid.len());
+            let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+            for i in (0..n).rev() {
+                for j in (0..m).rev() {
+                    lcs[i][j] = if a_mid[i] == b_mid[j] {
+                        lcs[i + 1][j + 1] + 1
+                    } else {
+                        lcs[i + 1][j].max(lcs[i][j + 1])
+                    };
+                }
+            }
+            let (mut i, mut j) = (0, 0);
+            while i < n && j < m {
+                if a_mid[i] == b_mid[j] {
+                    script.push(DiffLine::Equal(a_mid[i]));
+                    i += 1;
+                    j += 1;
+                } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                    script.push(DiffLine::Delete(a_mid[i]));
+                    i += 1;
+                } else {
+                    script.push(DiffLine::Insert(b_mid[j]));
+                    j += 1;
+                }
+            }
+            script.extend(a_mid[i..].iter().map(|l| DiffLine::Delete(*l)));
+            script.extend(b_mid[j..].iter().map(|l| DiffLine::Insert(*l)));
+        }
+        script.extend(a[a.len() - suffix..].iter().map(|l| DiffLine::Equal(*l)));
+        script
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * unified diffの作成テスト
+     */
+    fn unified_test() {
+        let stupid = "a\nb\nc\nd\n";
+        let smart = "a\nB\nc\nd\ne\n";
+        assert_eq!(
+            TextDiff::unified(stupid, smart, 1),
+            vec![
+                "--- stupid",
+                "+++ smart",
+                "@@ -1,4 +1,5 @@",
+                " a",
+                "-b",
+                "+B",
+                " c",
+                " d",
+                "+e",
+            ]
+        );
+        assert_eq!(TextDiff::unified(stupid, stupid, 3).len(), 2);
+
+        /* 末尾の改行の有無のみが異なる場合 */
+        assert_eq!(
+            TextDiff::unified("a\nb\n", "a\nb", 1),
+            vec![
+                "--- stupid",
+                "+++ smart",
+                "@@ -1,2 +1,2 @@",
+                " a",
+                "-b",
+                "+b",
+                "\\ No newline at end of file",
+            ]
+        );
+    }
+}
@@ -1,32 +1,222 @@
 use anyhow::{bail, Result};
 use lazy_static::lazy_static;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
+pub use crate::config::Config;
 pub use crate::print_console::PrintError;
 
 pub struct MyFileIO {}
 
-#[derive(Debug, Deserialize)]
+/**
+ * 設定 (全ての項目に既定値があり，settings.tomlなどで指定した項目のみ上書きされる)
+ */
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettingsClass {
     pub execution: ExecutionSettings,
     pub logging: LoggingSettings,
+    pub compile: CompileSettings,
+    pub language: HashMap<String, LanguageSettings>,
+    pub judge: JudgeSettings,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExecutionSettings {
     pub max_output_len: i32,
     pub max_output_line: i32,
     pub time_limit: u64,
+    pub memory_limit: u64,
+    pub time_measure: TimeMeasure,
+    pub jobs: Option<usize>,
     pub bin_extension: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl Default for ExecutionSettings {
+    fn default() -> Self {
+        ExecutionSettings {
+            max_output_len: 200,
+            max_output_line: 4,
+            time_limit: 2000,
+            memory_limit: 0,
+            time_measure: TimeMeasure::Wall,
+            jobs: None,
+            bin_extension: String::from("out"),
+        }
+    }
+}
+
+/**
+ * TLEの判定に使う時間
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeMeasure {
+    /* 実行時間 (wall-clock time) */
+    #[default]
+    Wall,
+    /* CPU時間 (user + sys) */
+    Cpu,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LoggingSettings {
     pub dump_exe_result: bool,
+    pub show_diff: bool,
+    pub save_diff: bool,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        LoggingSettings {
+            dump_exe_result: false,
+            show_diff: true,
+            save_diff: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileSettings {
+    pub compiler: String,
+    pub std: String,
+    pub optimization: String,
+    pub flags: Vec<String>,
+    pub generator: Option<CompileOverride>,
+    pub smart: Option<CompileOverride>,
+    pub stupid: Option<CompileOverride>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JudgeSettings {
+    pub compare: CompareMode,
+    pub abs_eps: f64,
+    pub rel_eps: f64,
+}
+
+/**
+ * smartとstupidの出力の比較方法
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompareMode {
+    /* 完全一致 */
+    Exact,
+    /* 行ごとに比較 (行末の空白と末尾の空行を無視) */
+    Lines,
+    /* 空白区切りのトークンごとに比較 */
+    Tokens,
+    /* トークンごとに比較し，数値は誤差を許容 */
+    Float,
+}
+
+impl Default for JudgeSettings {
+    fn default() -> Self {
+        JudgeSettings {
+            compare: CompareMode::Exact,
+            abs_eps: 1e-6,
+            rel_eps: 1e-6,
+        }
+    }
+}
+
+/**
+ * 拡張子ごとのコンパイル・実行コマンド
+ * {source}, {bin}, {bin_dir} はそれぞれソースファイル，実行形式ファイル，出力ディレクトリへのパスに置換される
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageSettings {
+    pub compile: Option<String>,
+    pub run: String,
+}
+
+/**
+ * プログラムごとに上書きするコンパイル設定 (指定した項目のみ上書きする)
+ */
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CompileOverride {
+    pub compiler: Option<String>,
+    pub std: Option<String>,
+    pub optimization: Option<String>,
+    pub flags: Option<Vec<String>>,
+}
+
+impl Default for CompileSettings {
+    fn default() -> Self {
+        CompileSettings {
+            compiler: String::from("g++"),
+            std: String::from("c++1z"),
+            optimization: String::from("-O3"),
+            flags: vec![
+                String::from("-fsanitize=undefined"),
+                String::from("-fno-sanitize-recover=undefined"),
+            ],
+            generator: None,
+            smart: None,
+            stupid: None,
+        }
+    }
+}
+
+impl CompileSettings {
+    /**
+     * プログラムのコンパイルコマンドを取得
+     * @param program generator, smart or stupid
+     * @return (コンパイラ, コンパイルフラグ)
+     */
+    pub fn get_command(&self, program: &str) -> (String, Vec<String>) {
+        let default_override = CompileOverride::default();
+        let program_override = match program {
+            "generator" => self.generator.as_ref(),
+            "smart" => self.smart.as_ref(),
+            "stupid" => self.stupid.as_ref(),
+            _ => None,
+        }
+        .unwrap_or(&default_override);
+
+        let compiler = program_override.compiler.as_ref().unwrap_or(&self.compiler);
+        let std = program_override.std.as_ref().unwrap_or(&self.std);
+        let optimization = program_override
+            .optimization
+            .as_ref()
+            .unwrap_or(&self.optimization);
+        let flags = program_override.flags.as_ref().unwrap_or(&self.flags);
+
+        let mut args = Vec::new();
+        if !std.is_empty() {
+            args.push(format!("-std={}", std));
+        }
+        if !optimization.is_empty() {
+            args.push(optimization.clone());
+        }
+        args.extend(flags.iter().cloned());
+        /* ローカルヘッダはテストディレクトリからインクルードする */
+        args.push(String::from("-I"));
+        args.push(String::from("."));
+        (compiler.clone(), args)
+    }
+}
+
+/**
+ * コマンドライン引数による設定の上書き (SETTINGを初めて参照する前に設定する)
+ */
+#[derive(Debug, Default, Clone)]
+pub struct SettingsOverride {
+    /* テストディレクトリ */
+    pub dir: Option<PathBuf>,
+    /* Time Limit(ms) */
+    pub time_limit: Option<u64>,
+    /* テストケースの並列実行数 */
+    pub jobs: Option<usize>,
 }
 
 lazy_static! {
@@ -35,25 +225,58 @@ lazy_static! {
      * SETTING.execution.max_output_len のようにアクセスする
      */
     pub static ref SETTING: SettingsClass = {
-        let settings_path = MyFileIO::get_root_path().clone().join("settings.toml");
-        let settings = MyFileIO::read_settings(settings_path.clone()).unwrap();
-        settings
+        let root_path = MyFileIO::get_root_path();
+        /* 設定に問題がある場合は，問題点を表示して終了する */
+        match Config::load_or_report(&root_path, &MyFileIO::get_override()) {
+            Ok(loaded) => loaded.settings,
+            Err(_) => std::process::exit(1),
+        }
     };
+
+    static ref SETTINGS_OVERRIDE: RwLock<SettingsOverride> = RwLock::new(SettingsOverride::default());
 }
 
 impl MyFileIO {
     /**
-     * testへパスを取得
-     * @return testへパス
+     * コマンドライン引数による設定の上書きを登録
+     * @param settings_override 上書きする設定
+     */
+    pub fn set_override(settings_override: SettingsOverride) {
+        *SETTINGS_OVERRIDE.write().unwrap() = settings_override;
+    }
+
+    /**
+     * コマンドライン引数による設定の上書きを取得
+     * @return 上書きする設定
+     */
+    pub fn get_override() -> SettingsOverride {
+        SETTINGS_OVERRIDE.read().unwrap().clone()
+    }
+
+    /**
+     * テストディレクトリへのパスを取得
+     * --dirが指定されていればそのディレクトリ，なければカレントディレクトリから親へ遡って最初にsettings.tomlがあるディレクトリ
+     * @return テストディレクトリへの絶対パス (見つからない場合はカレントディレクトリ)
      */
     pub fn get_root_path() -> PathBuf {
-        let mut exec_path = env::current_dir().unwrap();
-        if exec_path.file_name().unwrap().to_str().unwrap() == String::from("cpstt")
-            && Path::new("test").exists()
-        {
-            exec_path.push("test");
+        let current_dir = env::current_dir().unwrap();
+        if let Some(dir) = &SETTINGS_OVERRIDE.read().unwrap().dir {
+            let dir = current_dir.join(dir);
+            return dir.canonicalize().unwrap_or(dir);
         }
-        exec_path
+        MyFileIO::find_root_path(&current_dir).unwrap_or(current_dir)
+    }
+
+    /**
+     * settings.tomlがあるディレクトリを親ディレクトリへ遡って探す (cargoがCargo.tomlを探すのと同様)
+     * @param start_path 探索を始めるディレクトリ
+     * @return settings.tomlがある最も近いディレクトリ (見つからない場合はNone)
+     */
+    pub fn find_root_path(start_path: &Path) -> Option<PathBuf> {
+        start_path
+            .ancestors()
+            .find(|dir| dir.join("settings.toml").is_file())
+            .map(Path::to_path_buf)
     }
 
     /**
@@ -82,7 +305,14 @@ impl MyFileIO {
         let paths = MyFileIO::get_path_list(dir_path)?;
         for path in paths.iter() {
             let extension = path.extension().unwrap().to_str().unwrap();
-            if extension == "in" || extension == "diff" || extension == SETTING.execution.bin_extension {
+            if extension == "in"
+                || extension == "diff"
+                || extension == "err"
+                || extension == "status"
+                || extension == "hash"
+                || extension == "patch"
+                || extension == SETTING.execution.bin_extension
+            {
                 fs::remove_file(path)?;
             } else {
                 PrintError::print_error(format!(
@@ -121,27 +351,14 @@ impl MyFileIO {
         Ok(file_content)
     }
 
-    /**
-     * 設定ファイルの読み込み
-     * @param path settings.tomlへの絶対パス
-     * @return 異常終了: エラー
-     *         正常終了: 実行結果の文字列
-     */
-    pub fn read_settings(path: PathBuf) -> Result<SettingsClass, anyhow::Error> {
-        let settings_content = MyFileIO::read_file(String::from(path.to_str().unwrap()))?;
-        let settings: SettingsClass = toml::from_str(&settings_content).unwrap();
-        Ok(settings)
-    }
     /**
      * 空ディレクトリの生成
      * @param
      * @return
      */
     pub fn make_dir(path: PathBuf) -> Result<()> {
-        match fs::create_dir(path) {
-            Err(_) => {}
-            Ok(_) => {}
-        }
+        /* 既に存在する場合は何もしない */
+        let _ = fs::create_dir(path);
         Ok(())
     }
     /**
@@ -156,7 +373,7 @@ impl MyFileIO {
         let testcase_path = root_path.clone().join("testcase");
         MyFileIO::make_dir(testcase_path.clone())?;
 
-        let paths = vec!["bin", "smart", "stupid"];
+        let paths = vec!["bin", "smart", "stupid", "diff", "shrink"];
 
         for path in paths {
             let base_path = cpstt_out_path.clone().join(path);
@@ -166,3 +383,24 @@ impl MyFileIO {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * settings.tomlがあるディレクトリの探索テスト
+     */
+    fn find_root_path_test() {
+        let root_path =
+            env::temp_dir().join(format!("cpstt_find_root_path_{}", std::process::id()));
+        let sub_path = root_path.join("testcase/sub");
+        fs::create_dir_all(&sub_path).unwrap();
+        File::create(root_path.join("settings.toml")).unwrap();
+
+        assert_eq!(MyFileIO::find_root_path(&sub_path), Some(root_path.clone()));
+        assert_eq!(MyFileIO::find_root_path(&root_path), Some(root_path.clone()));
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+}
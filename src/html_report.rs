@@ -0,0 +1,359 @@
+use anyhow::Result;
+use std::path::Path;
+
+pub use crate::diff::TextDiff;
+pub use crate::fileio::{MyFileIO, TimeMeasure};
+pub use crate::report::{ExecReport, Report, TestcaseReport};
+
+/* 実行時間のヒストグラムのTime Limit以内の区間数 */
+const HISTOGRAM_BINS: u64 = 10;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: right; }
+th { background: #f4f4f4; }
+td.label { text-align: left; white-space: nowrap; }
+.AC { color: #2e7d32; font-weight: bold; }
+.WA, .TLE, .MLE, .RE { color: #e65100; font-weight: bold; }
+.histograms { display: flex; flex-wrap: wrap; gap: 2em; }
+.bar { background: #1976d2; height: 0.9em; }
+.bar.over { background: #e65100; }
+td.bar-cell { width: 20em; text-align: left; }
+details { border: 1px solid #ccc; border-radius: 4px; margin-bottom: 0.8em; padding: 0.5em 1em; }
+summary { cursor: pointer; }
+pre { background: #f8f8f8; padding: 0.5em; overflow-x: auto; max-height: 30em; }
+.outputs { display: flex; gap: 1em; }
+.outputs > div { flex: 1; min-width: 0; }
+.diff-del { color: #c62828; }
+.diff-ins { color: #2e7d32; }
+.diff-hunk { color: #00838f; }
+";
+
+pub struct HtmlReport {}
+
+impl HtmlReport {
+    /**
+     * テストの結果をHTMLで書き込む
+     * @param report テストの結果
+     * @param path 保存先
+     * @return 正常終了の有無
+     */
+    pub fn write(report: &Report, path: &Path) -> Result<()> {
+        MyFileIO::write_file(&path.to_path_buf(), &HtmlReport::render(report))
+    }
+
+    /**
+     * テストの結果を1つの静的なHTMLに変換 (集計表，実行時間のヒストグラム，失敗したテストケースの詳細)
+     * @param report テストの結果
+     * @return HTML
+     */
+    pub fn render(report: &Report) -> String {
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n");
+        html += "<meta charset=\"UTF-8\">\n";
+        html += &format!(
+            "<title>cpstt report - {}</title>\n",
+            HtmlReport::escape(&report.root_path)
+        );
+        html += &format!("<style>{}</style>\n", STYLE);
+        html += "</head>\n<body>\n";
+        html += "<h1>cpstt report</h1>\n";
+        html += &format!(
+            "<p>{} (cpstt {}, created at {} (UNIX time){})</p>\n",
+            HtmlReport::escape(&report.root_path),
+            HtmlReport::escape(&report.cpstt_version),
+            report.created_at,
+            report
+                .config
+                .seed
+                .map_or_else(String::new, |seed| format!(", seed {}", seed))
+        );
+
+        html += "<h2>Summary</h2>\n";
+        html += &HtmlReport::summary_table(report);
+
+        html += "<h2>Time</h2>\n<div class=\"histograms\">\n";
+        html += &HtmlReport::histogram(report, "smart", |t| &t.smart);
+        html += &HtmlReport::histogram(report, "stupid", |t| &t.stupid);
+        html += "</div>\n";
+
+        html += "<h2>Failed testcases</h2>\n";
+        let failed: Vec<&TestcaseReport> = report
+            .testcases
+            .iter()
+            .filter(|testcase| testcase.verdict != "AC")
+            .collect();
+        if failed.is_empty() {
+            html += "<p>None</p>\n";
+        }
+        for testcase in failed {
+            html += &HtmlReport::failed_testcase(testcase);
+        }
+        html += "</body>\n</html>\n";
+        html
+    }
+
+    /**
+     * 判定ごとのテストケース数の表を作成
+     * @param report テストの結果
+     * @return HTML
+     */
+    fn summary_table(report: &Report) -> String {
+        let summary = &report.summary;
+        let counts = [
+            ("AC", summary.accepted),
+            ("WA", summary.wrong_answer),
+            ("TLE", summary.time_limit_exceeded),
+            ("MLE", summary.memory_limit_exceeded),
+            ("RE", summary.runtime_error),
+        ];
+        let mut html = String::from("<table>\n<tr>");
+        for (verdict, _) in &counts {
+            html += &format!("<th class=\"{0}\">{0}</th>", verdict);
+        }
+        html += "<th>total</th><th>skipped</th></tr>\n<tr>";
+        for (_, count) in &counts {
+            html += &format!("<td>{}</td>", count);
+        }
+        html += &format!(
+            "<td>{}</td><td>{}</td></tr>\n</table>\n",
+            summary.total, summary.skipped
+        );
+        html
+    }
+
+    /**
+     * 1つのプログラムの実行時間のヒストグラムを作成 (Time Limitを区間に分け，超えたものは最後の区間にまとめる)
+     * @param report テストの結果
+     * @param program_name プログラム名
+     * @param exec テストケースからプログラムの実行結果を取り出す関数
+     * @return HTML
+     */
+    fn histogram(
+        report: &Report,
+        program_name: &str,
+        exec: fn(&TestcaseReport) -> &ExecReport,
+    ) -> String {
+        let time_limit = report.config.time_limit_ms.max(1);
+        let width = time_limit.div_ceil(HISTOGRAM_BINS);
+        let mut counts = vec![0usize; HISTOGRAM_BINS as usize + 1];
+        for testcase in &report.testcases {
+            let exec = exec(testcase);
+            let time = match report.config.time_measure {
+                TimeMeasure::Wall => exec.time_ms,
+                TimeMeasure::Cpu => exec.cpu_time_ms,
+            };
+            let bin = if exec.status == "TLE" || time > time_limit {
+                HISTOGRAM_BINS
+            } else {
+                (time / width).min(HISTOGRAM_BINS - 1)
+            };
+            counts[bin as usize] += 1;
+        }
+        let max_count = counts.iter().copied().max().unwrap_or(0).max(1);
+
+        let measure = match report.config.time_measure {
+            TimeMeasure::Wall => "time",
+            TimeMeasure::Cpu => "cpu time",
+        };
+        let mut html = format!(
+            "<table>\n<caption>{} ({})</caption>\n<tr><th>ms</th><th></th><th>count</th></tr>\n",
+            HtmlReport::escape(program_name),
+            measure
+        );
+        for (bin, count) in counts.iter().enumerate() {
+            let bin = bin as u64;
+            let (label, class) = if bin == HISTOGRAM_BINS {
+                (format!("&gt; {}", time_limit), "bar over")
+            } else {
+                (
+                    format!("{} - {}", bin * width, ((bin + 1) * width).min(time_limit)),
+                    "bar",
+                )
+            };
+            html += &format!(
+                "<tr><td class=\"label\">{}</td><td class=\"bar-cell\"><div class=\"{}\" style=\"width: {}%\"></div></td><td>{}</td></tr>\n",
+                label,
+                class,
+                count * 100 / max_count,
+                count
+            );
+        }
+        html + "</table>\n"
+    }
+
+    /**
+     * 失敗したテストケースの詳細 (入力，smart/stupidの出力と差分) を折りたたみ可能な要素にする
+     * @param testcase 1テストケースの判定結果
+     * @return HTML
+     */
+    fn failed_testcase(testcase: &TestcaseReport) -> String {
+        let mut title = format!(
+            "<span class=\"{0}\">{0}</span>: {1}",
+            HtmlReport::escape(&testcase.verdict),
+            HtmlReport::escape(&testcase.name)
+        );
+        if let Some(culprit) = &testcase.culprit {
+            title += &format!(" ({})", HtmlReport::escape(culprit));
+        }
+        if !testcase.message.is_empty() {
+            title += &format!(" - {}", HtmlReport::escape(&testcase.message));
+        }
+
+        let mut html = format!("<details>\n<summary>{}</summary>\n", title);
+        html += "<h4>input</h4>\n";
+        html += &HtmlReport::pre(&Report::read_embedded(&Some(testcase.input_path.clone())));
+
+        let smart_output = Report::read_embedded(&testcase.smart.output_path);
+        let stupid_output = Report::read_embedded(&testcase.stupid.output_path);
+        html += "<div class=\"outputs\">\n";
+        for (program_name, exec, output) in [
+            ("smart", &testcase.smart, &smart_output),
+            ("stupid", &testcase.stupid, &stupid_output),
+        ] {
+            html += &format!(
+                "<div>\n<h4>{} ({})</h4>\n",
+                program_name,
+                HtmlReport::escape(&HtmlReport::describe(exec))
+            );
+            html += &HtmlReport::pre(output);
+            let stderr = Report::read_embedded(&exec.stderr_path);
+            if !stderr.is_empty() {
+                html += "<h4>stderr</h4>\n";
+                html += &HtmlReport::pre(&stderr);
+            }
+            html += "</div>\n";
+        }
+        html += "</div>\n";
+
+        if testcase.verdict == "WA" {
+            html += "<h4>diff</h4>\n<pre>";
+            for line in TextDiff::unified(&stupid_output, &smart_output, 3) {
+                let class = if line.starts_with("---") || line.starts_with("+++") {
+                    ""
+                } else if line.starts_with("@@") {
+                    "diff-hunk"
+                } else if line.starts_with('-') {
+                    "diff-del"
+                } else if line.starts_with('+') {
+                    "diff-ins"
+                } else {
+                    ""
+                };
+                html += &format!(
+                    "<span class=\"{}\">{}</span>\n",
+                    class,
+                    HtmlReport::escape(&line)
+                );
+            }
+            html += "</pre>\n";
+        }
+        html + "</details>\n"
+    }
+
+    /**
+     * 実行状態を1行にまとめる
+     * @param exec 実行結果
+     * @return 実行状態の説明
+     */
+    fn describe(exec: &ExecReport) -> String {
+        let mut description = exec.status.clone();
+        if let Some(detail) = &exec.detail {
+            description += &format!(": {}", detail);
+        }
+        description += &format!(
+            ", {} ms, cpu {} ms, {:.1} MB",
+            exec.time_ms,
+            exec.cpu_time_ms,
+            exec.memory_kb as f64 / 1024.0
+        );
+        description
+    }
+
+    /**
+     * 文字列を<pre>要素にする
+     * @param content 表示内容
+     * @return HTML
+     */
+    fn pre(content: &str) -> String {
+        format!("<pre>{}</pre>\n", HtmlReport::escape(content))
+    }
+
+    /**
+     * HTMLの特殊文字をエスケープ
+     * @param text 文字列
+     * @return エスケープした文字列
+     */
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MAX_EMBED_LEN;
+    use std::fs;
+
+    #[test]
+    /**
+     * 特殊文字のエスケープと長い出力の切り詰めのテスト
+     */
+    fn render_test() {
+        let root = std::env::temp_dir().join(format!("cpstt_html_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = |name: &str| root.join(name).to_string_lossy().into_owned();
+        fs::write(path("input.in"), "1 < 2 & 3\n").unwrap();
+        fs::write(path("smart.diff"), "x".repeat(MAX_EMBED_LEN + 1)).unwrap();
+        fs::write(path("smart.err"), "<error> & exit\n").unwrap();
+        fs::write(path("stupid.diff"), "<b>\n").unwrap();
+
+        let smart = serde_json::json!({
+            "status": "done", "detail": null, "exit_code": 0, "signal": null,
+            "time_ms": 5, "cpu_time_ms": 5, "memory_kb": 1024,
+            "output_path": path("smart.diff"), "stderr_path": path("smart.err")
+        });
+        let mut stupid = smart.clone();
+        stupid["output_path"] = serde_json::json!(path("stupid.diff"));
+        stupid["stderr_path"] = serde_json::Value::Null;
+        let report: Report = serde_json::from_value(serde_json::json!({
+            "schema_version": 1,
+            "cpstt_version": "1.1.1",
+            "created_at": 0,
+            "command": "run",
+            "root_path": "/work/a<b&c",
+            "config": {
+                "seed": null, "jobs": 1, "fail_fast": false, "time_limit_ms": 2000,
+                "memory_limit_mb": 1024, "time_measure": "wall", "compare": "exact",
+                "abs_eps": 0.0, "rel_eps": 0.0, "generator": null, "smart": null,
+                "stupid": null, "checker": null, "validator": null
+            },
+            "summary": {
+                "total": 1, "accepted": 0, "wrong_answer": 1, "time_limit_exceeded": 0,
+                "memory_limit_exceeded": 0, "runtime_error": 0, "skipped": 0
+            },
+            "testcases": [{
+                "name": "0_<sample>", "verdict": "WA", "culprit": null, "message": "",
+                "input_path": path("input.in"), "smart": smart, "stupid": stupid
+            }]
+        }))
+        .unwrap();
+
+        let html = HtmlReport::render(&report);
+        fs::remove_dir_all(&root).unwrap();
+        assert!(html.contains("<title>cpstt report - /work/a&lt;b&amp;c</title>"));
+        assert!(html.contains("<span class=\"WA\">WA</span>: 0_&lt;sample&gt;"));
+        assert!(html.contains("<pre>1 &lt; 2 &amp; 3\n</pre>"));
+        assert!(html.contains("<pre>&lt;error&gt; &amp; exit\n</pre>"));
+        assert!(html.contains("<span class=\"diff-del\">-&lt;b&gt;</span>"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains(&format!(
+            "{}\n... (truncated)</pre>",
+            "x".repeat(MAX_EMBED_LEN)
+        )));
+        assert!(!html.contains(&"x".repeat(MAX_EMBED_LEN + 1)));
+    }
+}
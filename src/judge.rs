@@ -0,0 +1,300 @@
+pub use crate::diff::TextDiff;
+pub use crate::fileio::{CompareMode, JudgeSettings};
+
+pub struct Judge {}
+
+/**
+ * 出力が最初に異なる位置 (行・列は1始まりで，smartの出力上の位置)
+ */
+#[derive(Debug, PartialEq)]
+pub struct Difference {
+    pub line: usize,
+    pub column: usize,
+    /* stupidの該当部分 (存在しない場合はNone) */
+    pub expected: Option<String>,
+    /* smartの該当部分 (存在しない場合はNone) */
+    pub found: Option<String>,
+}
+
+impl Judge {
+    /**
+     * smartとstupidの出力を設定された方法で比較
+     * @param smart smartの出力
+     * @param stupid stupidの出力
+     * @param settings 比較方法の設定
+     * @return 一致していればtrue
+     */
+    pub fn compare(smart: &str, stupid: &str, settings: &JudgeSettings) -> bool {
+        match settings.compare {
+            CompareMode::Exact => smart == stupid,
+            CompareMode::Lines => Judge::trimmed_lines(smart) == Judge::trimmed_lines(stupid),
+            CompareMode::Tokens => smart.split_whitespace().eq(stupid.split_whitespace()),
+            CompareMode::Float => {
+                let smart_tokens: Vec<&str> = smart.split_whitespace().collect();
+                let stupid_tokens: Vec<&str> = stupid.split_whitespace().collect();
+                smart_tokens.len() == stupid_tokens.len()
+                    && smart_tokens.iter().zip(stupid_tokens.iter()).all(|(a, b)| {
+                        Judge::float_token_eq(a, b, settings.abs_eps, settings.rel_eps)
+                    })
+            }
+        }
+    }
+
+    /**
+     * 出力が最初に異なる位置を探す (行単位の比較では行，トークン単位の比較ではトークンを返す)
+     * @param smart smartの出力
+     * @param stupid stupidの出力
+     * @param settings 比較方法の設定
+     * @return 最初に異なる位置 (一致している場合はNone)
+     */
+    pub fn first_difference(
+        smart: &str,
+        stupid: &str,
+        settings: &JudgeSettings,
+    ) -> Option<Difference> {
+        match settings.compare {
+            CompareMode::Exact | CompareMode::Lines => {
+                let (smart_lines, stupid_lines): (Vec<&str>, Vec<&str>) =
+                    if settings.compare == CompareMode::Exact {
+                        (TextDiff::split_lines(smart), TextDiff::split_lines(stupid))
+                    } else {
+                        (Judge::trimmed_lines(smart), Judge::trimmed_lines(stupid))
+                    };
+                for i in 0..smart_lines.len().max(stupid_lines.len()) {
+                    let (found, expected) = (smart_lines.get(i), stupid_lines.get(i));
+                    if found == expected {
+                        continue;
+                    }
+                    let column = match (found, expected) {
+                        (Some(found), Some(expected)) => {
+                            found
+                                .chars()
+                                .zip(expected.chars())
+                                .take_while(|(a, b)| a == b)
+                                .count()
+                                + 1
+                        }
+                        _ => 1,
+                    };
+                    return Some(Difference {
+                        line: i + 1,
+                        column,
+                        expected: expected.map(|s| s.to_string()),
+                        found: found.map(|s| s.to_string()),
+                    });
+                }
+                /* 全ての行が一致していれば，末尾の改行の有無のみが異なる */
+                if smart == stupid || settings.compare != CompareMode::Exact {
+                    return None;
+                }
+                let newline =
+                    |content: &str| Some(String::from("\\n")).filter(|_| content.ends_with('\n'));
+                Some(Difference {
+                    line: smart_lines.len().max(1),
+                    column: smart_lines.last().map_or(0, |l| l.chars().count()) + 1,
+                    expected: newline(stupid),
+                    found: newline(smart),
+                })
+            }
+            CompareMode::Tokens | CompareMode::Float => {
+                let smart_tokens = Judge::tokens_with_position(smart);
+                let stupid_tokens = Judge::tokens_with_position(stupid);
+                for i in 0..smart_tokens.len().max(stupid_tokens.len()) {
+                    let found = smart_tokens.get(i);
+                    let expected = stupid_tokens.get(i);
+                    let is_equal = match (found, expected) {
+                        (Some((_, _, a)), Some((_, _, b))) => {
+                            if settings.compare == CompareMode::Float {
+                                Judge::float_token_eq(a, b, settings.abs_eps, settings.rel_eps)
+                            } else {
+                                a == b
+                            }
+                        }
+                        _ => false,
+                    };
+                    if is_equal {
+                        continue;
+                    }
+                    /* smartのトークンが足りない場合は出力の末尾を指す */
+                    let (line, column) = match found {
+                        Some((line, column, _)) => (*line, *column),
+                        None => {
+                            let smart_lines = TextDiff::split_lines(smart);
+                            let line = smart_lines.len().max(1);
+                            let column = smart_lines.last().map_or(0, |l| l.chars().count()) + 1;
+                            (line, column)
+                        }
+                    };
+                    return Some(Difference {
+                        line,
+                        column,
+                        expected: expected.map(|(_, _, token)| token.to_string()),
+                        found: found.map(|(_, _, token)| token.to_string()),
+                    });
+                }
+                None
+            }
+        }
+    }
+
+    /**
+     * 空白区切りのトークンを行・列番号付きで列挙
+     * @param content 出力
+     * @return (行, 列, トークン) の一覧
+     */
+    fn tokens_with_position(content: &str) -> Vec<(usize, usize, &str)> {
+        let mut tokens = Vec::new();
+        for (line_index, line) in TextDiff::split_lines(content).into_iter().enumerate() {
+            let mut start = None;
+            for (column, (byte_index, c)) in line.char_indices().enumerate() {
+                match (c.is_whitespace(), start) {
+                    (false, None) => start = Some((column, byte_index)),
+                    (true, Some((start_column, start_byte))) => {
+                        tokens.push((
+                            line_index + 1,
+                            start_column + 1,
+                            &line[start_byte..byte_index],
+                        ));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some((start_column, start_byte)) = start {
+                tokens.push((line_index + 1, start_column + 1, &line[start_byte..]));
+            }
+        }
+        tokens
+    }
+
+    /**
+     * 各行の末尾の空白と，末尾の空行を取り除いた行の一覧
+     * @param content 出力
+     * @return 行の一覧
+     */
+    fn trimmed_lines(content: &str) -> Vec<&str> {
+        let mut lines: Vec<&str> = TextDiff::split_lines(content)
+            .into_iter()
+            .map(|line| line.trim_end())
+            .collect();
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+        lines
+    }
+
+    /**
+     * トークンを比較 (両方が数値なら誤差を許容する)
+     * @param a smartのトークン
+     * @param b stupidのトークン (期待値)
+     * @param abs_eps 許容する絶対誤差
+     * @param rel_eps 許容する相対誤差
+     * @return 一致していればtrue
+     */
+    fn float_token_eq(a: &str, b: &str, abs_eps: f64, rel_eps: f64) -> bool {
+        if a == b {
+            return true;
+        }
+        match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(x), Ok(y)) if x.is_finite() && y.is_finite() => {
+                let diff = (x - y).abs();
+                diff <= abs_eps || diff <= rel_eps * y.abs()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(compare: CompareMode) -> JudgeSettings {
+        JudgeSettings {
+            compare,
+            abs_eps: 1e-6,
+            rel_eps: 1e-6,
+        }
+    }
+
+    #[test]
+    /**
+     * 比較方法ごとの判定テスト
+     */
+    fn compare_test() {
+        let exact = settings(CompareMode::Exact);
+        assert!(Judge::compare("1 2\n", "1 2\n", &exact));
+        assert!(!Judge::compare("1 2 \n", "1 2\n", &exact));
+
+        let lines = settings(CompareMode::Lines);
+        assert!(Judge::compare("1 2  \n3\n\n", "1 2\n3", &lines));
+        assert!(!Judge::compare("1 2\n3\n", "1 2 3\n", &lines));
+
+        let tokens = settings(CompareMode::Tokens);
+        assert!(Judge::compare("1 2\n3\n", "1  2 3", &tokens));
+        assert!(!Judge::compare("1 2\n", "1 2 3", &tokens));
+
+        let float = settings(CompareMode::Float);
+        assert!(Judge::compare("1.0000000\nYes", "1.0 Yes\n", &float));
+        assert!(Judge::compare("1000000.5", "1000000", &float));
+        assert!(!Judge::compare("1.001", "1.0", &float));
+        assert!(!Judge::compare("Yes", "No", &float));
+    }
+
+    #[test]
+    /**
+     * 最初に異なる位置の探索テスト
+     */
+    fn first_difference_test() {
+        let exact = settings(CompareMode::Exact);
+        assert_eq!(Judge::first_difference("1 2\n", "1 2\n", &exact), None);
+        assert_eq!(
+            Judge::first_difference("1 2\n3 5\n", "1 2\n3 4\n", &exact),
+            Some(Difference {
+                line: 2,
+                column: 3,
+                expected: Some(String::from("3 4")),
+                found: Some(String::from("3 5")),
+            })
+        );
+        /* 行が足りない場合と，末尾の改行のみが異なる場合 */
+        assert_eq!(
+            Judge::first_difference("1\n", "1\n2\n", &exact),
+            Some(Difference {
+                line: 2,
+                column: 1,
+                expected: Some(String::from("2")),
+                found: None,
+            })
+        );
+        assert_eq!(
+            Judge::first_difference("1\n2", "1\n2\n", &exact),
+            Some(Difference {
+                line: 2,
+                column: 2,
+                expected: Some(String::from("\\n")),
+                found: None,
+            })
+        );
+
+        let tokens = settings(CompareMode::Tokens);
+        assert_eq!(
+            Judge::first_difference("1 2\n  3\n", "1 2 4", &tokens),
+            Some(Difference {
+                line: 2,
+                column: 3,
+                expected: Some(String::from("4")),
+                found: Some(String::from("3")),
+            })
+        );
+        assert_eq!(
+            Judge::first_difference("1 2", "1 2 4", &tokens),
+            Some(Difference {
+                line: 1,
+                column: 4,
+                expected: Some(String::from("4")),
+                found: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use std::path::Path;
+
+pub use crate::diff::TextDiff;
+pub use crate::fileio::MyFileIO;
+pub use crate::report::{ExecReport, Report, TestcaseReport};
+
+pub struct JUnit {}
+
+impl JUnit {
+    /**
+     * テストの結果をJUnit XML形式で書き込む
+     * @param report テストの結果
+     * @param path 保存先
+     * @return 正常終了の有無
+     */
+    pub fn write(report: &Report, path: &Path) -> Result<()> {
+        MyFileIO::write_file(&path.to_path_buf(), &JUnit::render(report))
+    }
+
+    /**
+     * テストの結果をJUnit XML形式に変換
+     * (AC以外のテストケースは<failure>とし出力を添付する，実行しなかったテストケースは<skipped/>とする)
+     * @param report テストの結果
+     * @return XML
+     */
+    pub fn render(report: &Report) -> String {
+        let suite_name = Path::new(&report.root_path).file_name().map_or_else(
+            || String::from("cpstt"),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let failures = report.summary.total - report.summary.accepted;
+        let tests = report.summary.total + report.summary.skipped;
+        let time: u64 = report.testcases.iter().map(|t| t.smart.time_ms).sum();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml += &format!(
+            "<testsuites name=\"cpstt\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+            tests,
+            failures,
+            JUnit::seconds(time)
+        );
+        xml += &format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\">\n",
+            JUnit::escape(&suite_name),
+            tests,
+            failures,
+            report.summary.skipped,
+            JUnit::seconds(time)
+        );
+        xml += "    <properties>\n";
+        if let Some(seed) = report.config.seed {
+            xml += &format!("      <property name=\"seed\" value=\"{}\"/>\n", seed);
+        }
+        xml += &format!(
+            "      <property name=\"time_limit_ms\" value=\"{}\"/>\n",
+            report.config.time_limit_ms
+        );
+        xml += "    </properties>\n";
+        for testcase in &report.testcases {
+            xml += &JUnit::render_testcase(&suite_name, testcase);
+        }
+        for name in &report.skipped_testcases {
+            xml += &format!(
+                "    <testcase name=\"{}\" classname=\"{}\">\n      <skipped/>\n    </testcase>\n",
+                JUnit::escape(name),
+                JUnit::escape(&suite_name)
+            );
+        }
+        xml += "  </testsuite>\n";
+        xml += "</testsuites>\n";
+        xml
+    }
+
+    /**
+     * 1テストケースの<testcase>要素を作成
+     * @param suite_name テストスイート名
+     * @param testcase 1テストケースの判定結果
+     * @return XML
+     */
+    fn render_testcase(suite_name: &str, testcase: &TestcaseReport) -> String {
+        let mut xml = format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+            JUnit::escape(&testcase.name),
+            JUnit::escape(suite_name),
+            JUnit::seconds(testcase.smart.time_ms)
+        );
+        if testcase.verdict == "AC" {
+            return xml + "/>\n";
+        }
+        xml += ">\n";
+
+        /* 失敗の概要 (例: "TLE (stupid) - no output") */
+        let mut message = testcase.verdict.clone();
+        if let Some(culprit) = &testcase.culprit {
+            message += &format!(" ({})", culprit);
+        }
+        if !testcase.message.is_empty() {
+            message += &format!(" - {}", testcase.message);
+        }
+
+        /* 失敗の詳細: WAは差分，それ以外は原因となったプログラムの実行状態 */
+        let smart_output = Report::read_embedded(&testcase.smart.output_path);
+        let stupid_output = Report::read_embedded(&testcase.stupid.output_path);
+        let detail = if testcase.verdict == "WA" {
+            Report::truncate(TextDiff::unified(&stupid_output, &smart_output, 3).join("\n"))
+        } else {
+            let culprit = match testcase.culprit.as_deref() {
+                Some("stupid") => &testcase.stupid,
+                _ => &testcase.smart,
+            };
+            JUnit::describe(culprit)
+        };
+        xml += &format!(
+            "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+            JUnit::escape(&testcase.verdict),
+            JUnit::escape(&message),
+            JUnit::escape(&detail)
+        );
+
+        let input = Report::read_embedded(&Some(testcase.input_path.clone()));
+        let system_out = format!(
+            "input:\n{}\nsmart:\n{}\nstupid:\n{}",
+            input, smart_output, stupid_output
+        );
+        xml += &format!(
+            "      <system-out>{}</system-out>\n",
+            JUnit::escape(&system_out)
+        );
+        let smart_err = Report::read_embedded(&testcase.smart.stderr_path);
+        let stupid_err = Report::read_embedded(&testcase.stupid.stderr_path);
+        if !smart_err.is_empty() || !stupid_err.is_empty() {
+            let system_err = format!("smart:\n{}\nstupid:\n{}", smart_err, stupid_err);
+            xml += &format!(
+                "      <system-err>{}</system-err>\n",
+                JUnit::escape(&system_err)
+            );
+        }
+        xml + "    </testcase>\n"
+    }
+
+    /**
+     * 実行状態を文字列にする
+     * @param exec 実行結果
+     * @return 実行状態の説明
+     */
+    fn describe(exec: &ExecReport) -> String {
+        let mut description = format!("status: {}", exec.status);
+        if let Some(detail) = &exec.detail {
+            description += &format!(" ({})", detail);
+        }
+        if let Some(code) = exec.exit_code {
+            description += &format!(", exit code: {}", code);
+        }
+        if let Some(signal) = exec.signal {
+            description += &format!(", signal: {}", signal);
+        }
+        description += &format!(
+            ", time: {} ms, cpu time: {} ms, memory: {} KB",
+            exec.time_ms, exec.cpu_time_ms, exec.memory_kb
+        );
+        description
+    }
+
+    /**
+     * ミリ秒を秒の文字列にする
+     * @param ms ミリ秒
+     * @return 秒 (小数点以下3桁)
+     */
+    fn seconds(ms: u64) -> String {
+        format!("{}.{:03}", ms / 1000, ms % 1000)
+    }
+
+    /**
+     * XMLの特殊文字をエスケープし，XMLで使えない制御文字を取り除く
+     * @param text 文字列
+     * @return エスケープした文字列
+     */
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped += "&amp;",
+                '<' => escaped += "&lt;",
+                '>' => escaped += "&gt;",
+                '"' => escaped += "&quot;",
+                '\'' => escaped += "&apos;",
+                '\t' | '\n' | '\r' => escaped.push(c),
+                c if (c as u32) < 0x20 => {}
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MAX_EMBED_LEN;
+    use std::fs;
+
+    #[test]
+    /**
+     * XMLのエスケープテスト
+     */
+    fn escape_test() {
+        assert_eq!(
+            JUnit::escape("a < b && \"c\" > 'd'\u{1b}[0m\n"),
+            "a &lt; b &amp;&amp; &quot;c&quot; &gt; &apos;d&apos;[0m\n"
+        );
+        assert_eq!(JUnit::seconds(2005), "2.005");
+    }
+
+    #[test]
+    /**
+     * AC/WA/実行しなかったテストケースを含む結果の変換テスト
+     */
+    fn render_test() {
+        let root = std::env::temp_dir().join(format!("cpstt_junit_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = |name: &str| root.join(name).to_string_lossy().into_owned();
+        fs::write(path("input.in"), "1 < 2 & 3\n").unwrap();
+        fs::write(path("smart.diff"), "a".repeat(MAX_EMBED_LEN + 1)).unwrap();
+        fs::write(path("stupid.diff"), "b\n").unwrap();
+
+        let exec = serde_json::json!({
+            "status": "done", "detail": null, "exit_code": 0, "signal": null,
+            "time_ms": 5, "cpu_time_ms": 5, "memory_kb": 1000,
+            "output_path": path("smart.diff"), "stderr_path": null
+        });
+        let mut stupid = exec.clone();
+        stupid["output_path"] = serde_json::json!(path("stupid.diff"));
+        let testcase = |name: &str, verdict: &str| {
+            serde_json::json!({
+                "name": name, "verdict": verdict, "culprit": null, "message": "",
+                "input_path": path("input.in"), "smart": exec, "stupid": stupid
+            })
+        };
+        let report: Report = serde_json::from_value(serde_json::json!({
+            "schema_version": 1,
+            "cpstt_version": "1.1.1",
+            "created_at": 0,
+            "command": "run",
+            "root_path": "/work/abc000_a",
+            "config": {
+                "seed": null, "jobs": 1, "fail_fast": true, "time_limit_ms": 2000,
+                "memory_limit_mb": 1024, "time_measure": "wall", "compare": "exact",
+                "abs_eps": 0.0, "rel_eps": 0.0, "generator": null, "smart": null,
+                "stupid": null, "checker": null, "validator": null
+            },
+            "summary": {
+                "total": 2, "accepted": 1, "wrong_answer": 1, "time_limit_exceeded": 0,
+                "memory_limit_exceeded": 0, "runtime_error": 0, "skipped": 1
+            },
+            "testcases": [testcase("0_sample_00", "AC"), testcase("0_sample_01", "WA")],
+            "skipped_testcases": ["0_sample_02"]
+        }))
+        .unwrap();
+
+        let xml = JUnit::render(&report);
+        fs::remove_dir_all(&root).unwrap();
+        assert!(xml.contains("<testsuites name=\"cpstt\" tests=\"3\" failures=\"1\""));
+        assert!(xml.contains("<testsuite name=\"abc000_a\" tests=\"3\" failures=\"1\""));
+        assert!(
+            xml.contains("<testcase name=\"0_sample_00\" classname=\"abc000_a\" time=\"0.005\"/>")
+        );
+        assert!(xml.contains("<failure type=\"WA\" message=\"WA\">"));
+        assert!(xml.contains(
+            "<testcase name=\"0_sample_02\" classname=\"abc000_a\">\n      <skipped/>\n"
+        ));
+        assert!(xml.contains("1 &lt; 2 &amp; 3"));
+        assert!(xml.contains("... (truncated)"));
+        assert!(!xml.contains(&"a".repeat(MAX_EMBED_LEN + 1)));
+    }
+}
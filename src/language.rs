@@ -0,0 +1,306 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub use crate::fileio::{LanguageSettings, SETTING};
+pub use crate::print_console::PrintError;
+
+/* C++として[compile]の設定でコンパイルする拡張子 */
+const CPP_EXTENSIONS: [&str; 3] = ["cpp", "cc", "cxx"];
+/* 大きなシャドウメモリを予約するサニタイザ (-fsanitize=の値) */
+const SHADOW_MEMORY_SANITIZERS: [&str; 4] = ["address", "thread", "memory", "hwaddress"];
+
+/**
+ * 組み込みの言語設定 (settings.tomlの[language.<拡張子>]で上書きできる)
+ * @param extension ソースファイルの拡張子
+ * @return 言語設定
+ */
+fn builtin_language(extension: &str) -> Option<LanguageSettings> {
+    let (compile, run) = match extension {
+        "c" => (Some("gcc -O2 -I . -o {bin} {source}"), "{bin}"),
+        "rs" => (Some("rustc --edition 2018 -O -o {bin} {source}"), "{bin}"),
+        "py" => (None, "python3 {source}"),
+        "java" => (
+            Some("javac -d {bin_dir} {source}"),
+            "java -cp {bin_dir} Main",
+        ),
+        _ => return None,
+    };
+    Some(LanguageSettings {
+        compile: compile.map(String::from),
+        run: String::from(run),
+    })
+}
+
+/**
+ * generator/smart/stupidのソースファイルと，そのコンパイル・実行方法
+ */
+#[derive(Debug, Clone)]
+pub struct Program {
+    /* generator, smart or stupid */
+    pub name: String,
+    /* ソースファイルへの絶対パス */
+    pub source_path: PathBuf,
+    /* コンパイル結果の保存先 (ビルドキャッシュの判定に使う) */
+    pub artifact_path: PathBuf,
+    /* テストディレクトリへの絶対パス (コンパイル・実行時のカレントディレクトリ) */
+    root_path: PathBuf,
+    /* コンパイルコマンド (インタプリタ言語の場合はNone) */
+    compile_command: Option<Vec<String>>,
+    /* 実行コマンド */
+    run_command: Vec<String>,
+}
+
+impl Program {
+    /**
+     * テストディレクトリから name.<拡張子> を探す
+     * @param root_path テストディレクトリへの絶対パス
+     * @param name generator, smart or stupid
+     * @return 異常終了: エラー
+     *         正常終了: プログラム
+     */
+    pub fn find(root_path: &Path, name: &str) -> Result<Program> {
+        match Program::find_optional(root_path, name)? {
+            Some(program) => Ok(program),
+            None => {
+                PrintError::print_error(format!(
+                    "{}.* is not found in {}",
+                    name,
+                    root_path.to_string_lossy()
+                ));
+                bail!("Some Error is occurred!");
+            }
+        }
+    }
+
+    /**
+     * テストディレクトリから name.<拡張子> を探す (checkerなど省略可能なプログラム用)
+     * @param root_path テストディレクトリへの絶対パス
+     * @param name プログラム名
+     * @return 異常終了: エラー
+     *         正常終了: プログラム (存在しない場合はNone)
+     */
+    pub fn find_optional(root_path: &Path, name: &str) -> Result<Option<Program>> {
+        let mut candidates = Vec::new();
+        for entry in fs::read_dir(root_path)? {
+            let path = entry?.path();
+            if !path.is_file() || path.file_stem().map_or(true, |stem| stem != name) {
+                continue;
+            }
+            let extension = match path.extension() {
+                Some(extension) => extension.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            if Program::is_known_extension(&extension) {
+                candidates.push((path, extension));
+            }
+        }
+        candidates.sort();
+
+        match candidates.len() {
+            0 => Ok(None),
+            1 => {
+                let (source_path, extension) = candidates.remove(0);
+                Program::resolve(root_path, name, source_path, &extension).map(Some)
+            }
+            _ => {
+                let names: Vec<String> = candidates
+                    .iter()
+                    .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+                    .collect();
+                PrintError::print_error(format!(
+                    "There are multiple source files for {} ({})",
+                    name,
+                    names.join(", ")
+                ));
+                bail!("Some Error is occurred!");
+            }
+        }
+    }
+
+    /**
+     * 対応している拡張子かどうか
+     * @param extension 拡張子
+     * @return 対応していればtrue
+     */
+    fn is_known_extension(extension: &str) -> bool {
+        SETTING.language.contains_key(extension)
+            || CPP_EXTENSIONS.contains(&extension)
+            || builtin_language(extension).is_some()
+    }
+
+    /**
+     * 言語設定からコンパイル・実行コマンドを組み立てる
+     * @param root_path テストディレクトリへの絶対パス
+     * @param name generator, smart or stupid
+     * @param source_path ソースファイルへの絶対パス
+     * @param extension ソースファイルの拡張子
+     * @return 異常終了: エラー
+     *         正常終了: プログラム
+     */
+    fn resolve(
+        root_path: &Path,
+        name: &str,
+        source_path: PathBuf,
+        extension: &str,
+    ) -> Result<Program> {
+        let mut bin_path = root_path.join("cpstt_out/bin").join(name);
+        bin_path.set_extension(&SETTING.execution.bin_extension);
+        let bin_dir = root_path.join("cpstt_out/bin").join(name);
+
+        let (compile_command, run_command) = match SETTING.language.get(extension) {
+            Some(language) => (
+                language.compile.as_ref().map(|c| Program::split_command(c)),
+                Program::split_command(&language.run),
+            ),
+            None if CPP_EXTENSIONS.contains(&extension) => {
+                /* C++は[compile]の設定を使う */
+                let (compiler, flags) = SETTING.compile.get_command(name);
+                let mut command = vec![compiler];
+                command.extend(flags);
+                command.extend(
+                    ["-o", "{bin}", "{source}"]
+                        .iter()
+                        .map(|token| String::from(*token)),
+                );
+                (Some(command), vec![String::from("{bin}")])
+            }
+            None => {
+                let language = builtin_language(extension).unwrap();
+                (
+                    language.compile.as_ref().map(|c| Program::split_command(c)),
+                    Program::split_command(&language.run),
+                )
+            }
+        };
+        if run_command.is_empty() || compile_command.as_ref().is_some_and(|c| c.is_empty()) {
+            PrintError::print_error(format!("Command for .{} files is empty", extension));
+            bail!("Some Error is occurred!");
+        }
+
+        /* {bin}を使わない言語(Javaなど)は{bin_dir}をコンパイル結果とする */
+        let uses_bin = compile_command
+            .as_ref()
+            .is_some_and(|c| c.iter().any(|token| token.contains("{bin}")));
+        let artifact_path = if uses_bin {
+            bin_path.clone()
+        } else {
+            bin_dir.clone()
+        };
+
+        let expand = |command: Vec<String>| -> Vec<String> {
+            command
+                .iter()
+                .map(|token| {
+                    token
+                        .replace("{source}", source_path.to_str().unwrap())
+                        .replace("{bin_dir}", bin_dir.to_str().unwrap())
+                        .replace("{bin}", bin_path.to_str().unwrap())
+                })
+                .collect()
+        };
+        Ok(Program {
+            name: String::from(name),
+            artifact_path,
+            root_path: root_path.to_path_buf(),
+            compile_command: compile_command.map(expand),
+            run_command: expand(run_command),
+            source_path,
+        })
+    }
+
+    /**
+     * コマンド文字列を空白で分割
+     * @param command コマンド文字列
+     * @return コマンドと引数
+     */
+    fn split_command(command: &str) -> Vec<String> {
+        command.split_whitespace().map(String::from).collect()
+    }
+
+    /**
+     * コンパイルコマンド
+     * @return コンパイルコマンドと引数 (コンパイル不要な場合はNone)
+     */
+    pub fn compile_command(&self) -> Option<&[String]> {
+        self.compile_command.as_deref()
+    }
+
+    /**
+     * Javaの場合，実行するクラス(実行コマンドの最後の引数．組み込みの設定ではMain)がコンパイル結果に含まれるか確認する
+     * @return 異常終了: エラー (クラスファイルが生成されていない場合)
+     *         正常終了: 正常終了の有無
+     */
+    pub fn check_main_class(&self) -> Result<()> {
+        if self
+            .source_path
+            .extension()
+            .map_or(true, |ext| ext != "java")
+            || !self.artifact_path.is_dir()
+        {
+            return Ok(());
+        }
+        let main_class = self.run_command.last().unwrap();
+        let class_path = self
+            .artifact_path
+            .join(main_class.replace('.', "/"))
+            .with_extension("class");
+        if class_path.is_file() {
+            return Ok(());
+        }
+
+        let mut classes: Vec<String> = fs::read_dir(&self.artifact_path)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "class" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_string_lossy().into_owned())
+            })
+            .collect();
+        classes.sort();
+        PrintError::print_error(format!(
+            "{} does not define class {} (found: {}). Rename the class with main to {} (without public) or change the run command in [language.java]",
+            self.source_path.file_name().unwrap().to_string_lossy(),
+            main_class,
+            classes.join(", "),
+            main_class
+        ));
+        bail!("Some Error is occurred!");
+    }
+
+    /**
+     * RLIMIT_DATAでメモリを制限できるか
+     * (コンパイルした実行形式ファイルを直接実行し，シャドウメモリを使うサニタイザを使わない場合のみ．
+     * JavaやAddressSanitizerは使用量に関わらず大きなメモリ領域を確保するため)
+     * @return 制限できればtrue
+     */
+    pub fn limits_data_segment(&self) -> bool {
+        let uses_shadow_memory = |token: &String| {
+            token.strip_prefix("-fsanitize=").is_some_and(|sanitizers| {
+                sanitizers
+                    .split(',')
+                    .any(|sanitizer| SHADOW_MEMORY_SANITIZERS.contains(&sanitizer))
+            })
+        };
+        self.run_command[0] == self.artifact_path.to_string_lossy()
+            && !self
+                .compile_command
+                .iter()
+                .flatten()
+                .any(uses_shadow_memory)
+    }
+
+    /**
+     * 実行用のCommandを生成
+     * @return 実行コマンド
+     */
+    pub fn run_command(&self) -> Command {
+        let mut command = Command::new(&self.run_command[0]);
+        command
+            .args(&self.run_command[1..])
+            .current_dir(&self.root_path);
+        command
+    }
+}
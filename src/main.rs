@@ -1,18 +1,59 @@
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use clap::Clap;
 // use toml::to_string;
-use std::os::unix::io::{AsRawFd, FromRawFd};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::mem;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+mod build_cache;
+mod config;
+mod diff;
 mod fileio;
+mod html_report;
+mod judge;
+mod junit;
+mod language;
+mod memory_limit;
 mod print_console;
-pub use crate::fileio::{MyFileIO, SETTING};
+mod report;
+mod shrink;
+mod templates;
+mod verdict;
+mod worker_pool;
+pub use crate::build_cache::BuildCache;
+pub use crate::config::{Config, ConfigSource};
+pub use crate::diff::TextDiff;
+pub use crate::fileio::{MyFileIO, SettingsOverride, TimeMeasure, SETTING};
+pub use crate::html_report::HtmlReport;
+pub use crate::judge::Judge;
+pub use crate::junit::JUnit;
+pub use crate::language::Program;
+pub use crate::memory_limit::MemoryLimit;
 pub use crate::print_console::{PrintColorize, PrintError};
+pub use crate::report::{
+    ConfigReport, ExecReport, Report, SummaryReport, TestcaseReport, SCHEMA_VERSION,
+};
+pub use crate::shrink::Shrinker;
+pub use crate::templates::Templates;
+pub use crate::verdict::{ExecResult, ExecStatus, Verdict, VerdictSummary};
+pub use crate::worker_pool::WorkerPool;
+
+/* CPU時間でTLEを判定する場合に，実行時間がTime Limitの何倍を超えたら打ち切るか */
+const CPU_MODE_WALL_FACTOR: u32 = 3;
+/* checker/validatorの実行時間の上限がTime Limitの何倍か */
+const TESTLIB_TIME_LIMIT_FACTOR: u32 = 5;
+/* testlibでchecker自体の異常 (_fail) を表す終了コード */
+const CHECKER_FAIL_EXIT_CODE: i32 = 3;
+/* 実行終了後にパイプが閉じられるのを待つ時間の上限 */
+const PIPE_JOIN_TIMEOUT: Duration = Duration::from_secs(1);
 
 #[derive(Clap, Debug)]
 #[clap(
@@ -21,10 +62,200 @@ pub use crate::print_console::{PrintColorize, PrintError};
     author = env!("CARGO_PKG_AUTHORS"),
     about= env!("CARGO_PKG_DESCRIPTION")
 )]
-struct Opts {}
+struct Opts {
+    /// テストディレクトリ (省略時はカレントディレクトリ)
+    #[clap(long, global = true, value_name = "path", parse(from_os_str))]
+    dir: Option<PathBuf>,
+    /// Time Limit(ms) (settings.tomlのtime_limitを上書きする)
+    #[clap(long, global = true, value_name = "ms")]
+    time_limit: Option<u64>,
+    /// generatorの第2引数に渡すシード値 (stressでは1回目に使用するシード値)
+    #[clap(short, long, global = true)]
+    seed: Option<u64>,
+    /// 指定したパターンに一致するテストケースのみ実行する (*, ?が使える．複数指定可)
+    #[clap(long, global = true, value_name = "pattern", number_of_values = 1)]
+    only: Vec<String>,
+    /// キャッシュを無視して全てのプログラムを再コンパイルする
+    #[clap(long, global = true)]
+    rebuild: bool,
+    /// テストケースの並列実行数 (settings.tomlのjobsを上書きする．未設定ならCPUのコア数)
+    #[clap(short, long, global = true)]
+    jobs: Option<usize>,
+    /// テストケースを1つずつ実行する (実行時間を正確に測る場合に使う)
+    #[clap(long, global = true)]
+    serial: bool,
+    /// AC以外の判定が出たら残りのテストケースを実行せずに終了する
+    #[clap(long, global = true)]
+    fail_fast: bool,
+    /// テストの結果をJUnit XML形式で指定したファイルに保存する (CIで使う)
+    #[clap(long, global = true, value_name = "path", parse(from_os_str))]
+    junit: Option<PathBuf>,
+    #[clap(subcommand)]
+    subcmd: Option<SubCommand>,
+}
+
+#[derive(Clap, Debug)]
+enum SubCommand {
+    /// generator→smart→stupid→比較 を1回実行する (サブコマンドを省略した場合と同じ)
+    Run,
+    /// WAが見つかるまでgeneratorのシード値を変えて繰り返しテストする
+    Stress(StressOpts),
+    /// generatorを実行してtestcase/にテストケースを生成する
+    Gen,
+    /// testcase/のテストケースでsmart/stupidを実行し，出力をcpstt_out/に保存する
+    Exec(ExecOpts),
+    /// cpstt_out/に保存されたsmartとstupidの出力を比較する
+    Compare,
+    /// 失敗するテストケースを，失敗し続ける範囲で最小化する
+    Shrink(ShrinkOpts),
+    /// 前回のテストの結果 (cpstt_out/report.json) を表示する
+    Report(ReportOpts),
+    /// 設定を表示する
+    Config(ConfigOpts),
+    /// テストケースと実行結果を削除する
+    Clean(CleanOpts),
+    /// テンプレートから新しい問題のテストディレクトリを作成する
+    Init(InitOpts),
+}
+
+#[derive(Clap, Debug)]
+struct StressOpts {
+    /// 最大反復回数 (省略時は無制限)
+    #[clap(short = 'n', long)]
+    iterations: Option<u64>,
+    /// 実行時間の上限(秒) (省略時は無制限)
+    #[clap(short, long)]
+    time_budget: Option<u64>,
+    /// 失敗が見つかったらテストケースを最小化する
+    #[clap(long)]
+    shrink: bool,
+    /// 停止する判定結果 (カンマ区切り．例: WA,RE)
+    #[clap(
+        long,
+        default_value = "WA",
+        use_delimiter = true,
+        possible_values = &["WA", "TLE", "MLE", "RE"]
+    )]
+    stop_on: Vec<Verdict>,
+}
+
+/**
+ * テストの実行方法 (コマンドライン引数と設定から決まる)
+ */
+struct RunOptions {
+    /* 実行したサブコマンド (report.jsonに記録する) */
+    command: &'static str,
+    /* キャッシュを無視して再コンパイルするか */
+    rebuild: bool,
+    /* 並列実行数 */
+    jobs: usize,
+    /* 最初にAC以外の判定が出たら残りのテストケースを実行しないか */
+    fail_fast: bool,
+    /* JUnit XMLの保存先 */
+    junit: Option<PathBuf>,
+    /* 実行するテストケース名のパターン (空の場合は全て) */
+    only: Vec<String>,
+}
+
+impl RunOptions {
+    /**
+     * コマンドライン引数と設定から作成
+     * @param opts コマンドライン引数
+     * @return テストの実行方法
+     */
+    fn new(opts: &Opts) -> Self {
+        /* 並列実行数 (--serial > 設定(-jを含む) > CPUのコア数) */
+        let jobs = if opts.serial {
+            1
+        } else {
+            SETTING
+                .execution
+                .jobs
+                .unwrap_or_else(WorkerPool::default_jobs)
+        };
+        let command = match &opts.subcmd {
+            Some(SubCommand::Stress(_)) => "stress",
+            Some(SubCommand::Exec(_)) => "exec",
+            Some(SubCommand::Compare) => "compare",
+            _ => "run",
+        };
+        RunOptions {
+            command,
+            rebuild: opts.rebuild,
+            jobs,
+            fail_fast: opts.fail_fast,
+            junit: opts.junit.clone(),
+            only: opts.only.clone(),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+struct ExecOpts {
+    /// 実行するプログラム (省略時はsmartとstupidの両方)
+    #[clap(possible_values = &["smart", "stupid"])]
+    program: Option<String>,
+}
+
+#[derive(Clap, Debug)]
+struct ShrinkOpts {
+    /// 最小化するテストケース (testcase/内のテストケース名またはファイルへのパス)
+    testcase: String,
+}
+
+#[derive(Clap, Debug)]
+struct ReportOpts {
+    /// 集計表・実行時間のヒストグラム・失敗したテストケースの詳細を1つのHTMLに出力する
+    #[clap(long)]
+    html: bool,
+    /// HTMLの保存先 (省略時は cpstt_out/report.html)
+    #[clap(short, long, value_name = "path", parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clap, Debug)]
+struct InitOpts {
+    /// 作成するディレクトリ (省略時は--dirまたはカレントディレクトリ)
+    #[clap(parse(from_os_str))]
+    dir: Option<PathBuf>,
+    /// テンプレート (single: 1ケース, multi: 複数ケース, graph: グラフ・木の入力)
+    #[clap(short, long, default_value = "single", possible_values = &Templates::NAMES)]
+    template: String,
+    /// 既に存在するファイルも上書きする
+    #[clap(long)]
+    force: bool,
+}
+
+#[derive(Clap, Debug)]
+struct ConfigOpts {
+    #[clap(subcommand)]
+    subcmd: ConfigSubCommand,
+}
+
+#[derive(Clap, Debug)]
+enum ConfigSubCommand {
+    /// 既定値・設定ファイル・環境変数・コマンドライン引数を重ねた設定と，各項目の読み込み元を表示する
+    Show,
+    /// 設定ファイル・環境変数を検証し，問題があれば位置と共に表示する
+    Validate,
+}
+
+#[derive(Clap, Debug)]
+struct CleanOpts {
+    /// コンパイルしたバイナリファイルとビルドキャッシュ (cpstt_out/bin/) も削除する
+    #[clap(long)]
+    all: bool,
+}
 
 fn main() -> Result<()> {
-    Opts::parse();
+    let opts = Opts::parse();
+
+    /* --dir, --time-limit はsettings.tomlより優先する */
+    MyFileIO::set_override(SettingsOverride {
+        dir: opts.dir.clone(),
+        time_limit: opts.time_limit,
+        jobs: opts.jobs,
+    });
 
     /* テストディレクトリへのパスを取得 */
     let root_path = MyFileIO::get_root_path();
@@ -32,450 +263,2340 @@ fn main() -> Result<()> {
     /* ロゴを出力 */
     print_logo()?;
 
+    match &opts.subcmd {
+        Some(SubCommand::Stress(stress_opts)) => {
+            stress(root_path, opts.seed, stress_opts, &RunOptions::new(&opts))?;
+        }
+        Some(SubCommand::Gen) => gen(&root_path, opts.seed, opts.rebuild)?,
+        Some(SubCommand::Exec(exec_opts)) => exec(
+            &root_path,
+            exec_opts.program.as_deref(),
+            &RunOptions::new(&opts),
+        )?,
+        Some(SubCommand::Compare) => {
+            compare(&root_path, &RunOptions::new(&opts))?;
+        }
+        Some(SubCommand::Shrink(shrink_opts)) => {
+            /* 最小化するテストケースを消さないよう，初期化は行わない */
+            MyFileIO::make_init_dir(root_path.clone())?;
+            compile_all(root_path.clone(), opts.rebuild)?;
+            let testcase_path = find_testcase(&root_path, &shrink_opts.testcase)?;
+            shrink(&root_path, &testcase_path)?;
+        }
+        Some(SubCommand::Report(report_opts)) => show_report(&root_path, report_opts)?,
+        Some(SubCommand::Config(config_opts)) => match config_opts.subcmd {
+            ConfigSubCommand::Show => show_config(&root_path)?,
+            ConfigSubCommand::Validate => validate_config(&root_path)?,
+        },
+        Some(SubCommand::Clean(clean_opts)) => clean(&root_path, clean_opts.all)?,
+        Some(SubCommand::Init(init_opts)) => init_dir(opts.dir.as_deref(), init_opts)?,
+        Some(SubCommand::Run) | None => {
+            run(root_path, opts.seed, &RunOptions::new(&opts))?;
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * 初期化とコンパイルを行い，テストを1回実行
+ * @param root_path テストディレクトリへの絶対パス
+ * @param seed generatorへ渡すシード値
+ * @param run_options テストの実行方法
+ * @return 異常終了: エラー
+ *         正常終了: 判定結果の集計
+ */
+fn run(root_path: PathBuf, seed: Option<u64>, run_options: &RunOptions) -> Result<VerdictSummary> {
     /* プログラムの初期化 */
     init(root_path.clone())?;
 
+    /* generator/smart/stupidをコンパイル */
+    compile_all(root_path.clone(), run_options.rebuild)?;
+
+    match test_once(root_path, seed, run_options)? {
+        Some(summary) => Ok(summary),
+        None => {
+            PrintError::print_error(String::from("There is no valid testcase"));
+            bail!("Some Error is occurred!");
+        }
+    }
+}
+
+/**
+ * コンパイル済みのプログラムで generator→smart→stupid→比較 を1回実行
+ * @param root_path テストディレクトリへの絶対パス
+ * @param seed generatorへ渡すシード値
+ * @param run_options テストの実行方法
+ * @return 異常終了: エラー
+ *         正常終了: 判定結果の集計 (validatorを通るテストケースが無い場合はNone)
+ */
+fn test_once(
+    root_path: PathBuf,
+    seed: Option<u64>,
+    run_options: &RunOptions,
+) -> Result<Option<VerdictSummary>> {
     /* generatorを実行 */
-    generator(root_path.clone())?;
+    generator(root_path.clone(), seed)?;
 
     /* generatorで生成したファイルパスの取得 */
     let testcase_dir_path = root_path.clone().join("testcase");
-    let testcase_path_list = MyFileIO::get_path_list(testcase_dir_path.clone())?;
+    let testcase_path_list = MyFileIO::get_path_list(testcase_dir_path)?;
+    if testcase_path_list.is_empty() {
+        PrintError::print_error(String::from("generator did not create any testcase"));
+        bail!("Some Error is occurred!");
+    }
+    let testcase_path_list = filter_testcases(testcase_path_list, &run_options.only)?;
 
-    /* smartなプログラムを実行 */
-    exec_user_program(
-        root_path.clone(),
-        &testcase_path_list,
-        String::from("smart"),
-    )?;
+    /* validatorがあれば制約を満たさないテストケースを除く */
+    let testcase_path_list = validate_testcases(&root_path, testcase_path_list, run_options.jobs)?;
+    if testcase_path_list.is_empty() {
+        return Ok(None);
+    }
 
-    /* stupidなプログラムを実行 */
-    exec_user_program(
-        root_path.clone(),
+    /* テストケースごとにsmartとstupidを実行して比較 */
+    let (summary, testcases, skipped) = exec_testcases(
+        &root_path,
         &testcase_path_list,
-        String::from("stupid"),
+        run_options.jobs,
+        run_options.fail_fast,
     )?;
 
-    /* smartとstupidを比較 */
-    compare_result(root_path.clone())?;
+    /* 結果を cpstt_out/report.json に保存 */
+    let report = make_report(&root_path, seed, run_options, &summary, testcases, skipped)?;
+    report.write(&root_path.join("cpstt_out/report.json"))?;
+    if let Some(junit_path) = &run_options.junit {
+        JUnit::write(&report, junit_path)?;
+    }
+    Ok(Some(summary))
+}
 
-    Ok(())
+/**
+ * テストの結果をまとめる
+ * @param root_path テストディレクトリへの絶対パス
+ * @param seed generatorへ渡したシード値
+ * @param run_options テストの実行方法
+ * @param summary 判定結果の集計
+ * @param testcases テストケースごとの判定結果
+ * @param skipped 実行しなかったテストケース名
+ * @return 異常終了: エラー
+ *         正常終了: テストの結果
+ */
+fn make_report(
+    root_path: &Path,
+    seed: Option<u64>,
+    run_options: &RunOptions,
+    summary: &VerdictSummary,
+    testcases: Vec<TestcaseReport>,
+    skipped: Vec<String>,
+) -> Result<Report> {
+    let source = |name: &str| -> Result<Option<String>> {
+        Ok(Program::find_optional(root_path, name)?
+            .map(|program| program.source_path.to_string_lossy().into_owned()))
+    };
+    let config = ConfigReport {
+        seed,
+        jobs: run_options.jobs,
+        fail_fast: run_options.fail_fast,
+        time_limit_ms: SETTING.execution.time_limit,
+        memory_limit_mb: SETTING.execution.memory_limit,
+        time_measure: SETTING.execution.time_measure,
+        compare: SETTING.judge.compare,
+        abs_eps: SETTING.judge.abs_eps,
+        rel_eps: SETTING.judge.rel_eps,
+        generator: source("generator")?,
+        smart: source("smart")?,
+        stupid: source("stupid")?,
+        checker: source("checker")?,
+        validator: source("validator")?,
+    };
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    Ok(Report {
+        schema_version: SCHEMA_VERSION,
+        cpstt_version: String::from(env!("CARGO_PKG_VERSION")),
+        created_at,
+        command: String::from(run_options.command),
+        root_path: root_path.to_string_lossy().into_owned(),
+        config,
+        summary: SummaryReport::new(summary, skipped.len()),
+        testcases,
+        skipped_testcases: skipped,
+    })
 }
 
 /**
- * CPSTTのロゴを出力
- * @param path テストディレクトリへの絶対パス
+ * 停止する判定結果 (既定ではWA) が見つかるまでシード値を変えて繰り返し実行
+ * @param root_path テストディレクトリへの絶対パス
+ * @param seed 1回目に使用するシード値 (Noneの場合はランダム)
+ * @param opts stressのオプション
+ * @param run_options テストの実行方法
+ * @return 異常終了: エラー
+ *         正常終了: 見つかった場合は (シード値, テストケース名)
+ */
+fn stress(
+    root_path: PathBuf,
+    seed: Option<u64>,
+    opts: &StressOpts,
+    run_options: &RunOptions,
+) -> Result<Option<(u64, String)>> {
+    let stop_on = opts
+        .stop_on
+        .iter()
+        .map(|verdict| verdict.to_string())
+        .collect::<Vec<String>>()
+        .join("/");
+    let first_seed = seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    let time_budget = opts.time_budget.map(Duration::from_secs);
+    let start = Instant::now();
+
+    /* プログラムの初期化とコンパイルは最初の1回のみ */
+    init(root_path.clone())?;
+    compile_all(root_path.clone(), run_options.rebuild)?;
+
+    let mut iteration = 0;
+    loop {
+        /* 終了条件の判定 */
+        if opts.iterations.is_some_and(|n| iteration >= n)
+            || time_budget.is_some_and(|t| start.elapsed() >= t)
+        {
+            println!(
+                "{} No {} was found. ({} iterations, {} sec)",
+                PrintColorize::print_cyan(String::from("[ stress ]")),
+                stop_on,
+                iteration,
+                start.elapsed().as_secs()
+            );
+            return Ok(None);
+        }
+
+        let seed = first_seed.wrapping_add(iteration);
+        iteration += 1;
+        println!(
+            "{} iteration {} (seed: {})",
+            PrintColorize::print_cyan(String::from("[ stress ]")),
+            iteration,
+            seed
+        );
+
+        clean_outputs(root_path.clone())?;
+        let summary = match test_once(root_path.clone(), Some(seed), run_options)? {
+            Some(summary) => summary,
+            None => {
+                /* 制約を満たすテストケースが無い場合は次のシード値で続ける */
+                PrintError::print_warning(format!(
+                    "There is no valid testcase at iteration {} (seed: {})",
+                    iteration, seed
+                ));
+                continue;
+            }
+        };
+        if let Some((testcase_name, verdict)) = summary.first_failure(&opts.stop_on) {
+            /* 見つかったテストケースはtestcase/とcpstt_out/に残る */
+            println!(
+                "{} {} is found at iteration {} (seed: {}). The testcase is kept in {}",
+                PrintColorize::print_cyan(String::from("[ stress ]")),
+                verdict.to_colored_string(),
+                iteration,
+                seed,
+                root_path.join("testcase").to_string_lossy()
+            );
+            if opts.shrink {
+                let mut testcase_path = root_path.join("testcase").join(testcase_name);
+                testcase_path.set_extension("in");
+                shrink(&root_path, &testcase_path)?;
+            }
+            return Ok(Some((seed, String::from(testcase_name))));
+        }
+    }
+}
+
+/**
+ * generatorをコンパイルして実行し，testcase/にテストケースを生成
+ * @param root_path テストディレクトリへの絶対パス
+ * @param seed generatorへ渡すシード値
+ * @param rebuild キャッシュを無視して再コンパイルするか
  * @return 正常終了の有無
  */
-fn print_logo() -> Result<()> {
-    println!(r"---------------------------------------------");
-    println!(r"|         __________  _________________     |");
-    println!(r"|        / ____/ __ \/ ___/_  __/_  __/     |");
-    println!(r"|       / /   / /_/ /\__ \ / /   / /        |");
-    println!(r"|      / /___/ ____/___/ // /   / /         |");
-    println!(r"|      \____/_/    /____//_/   /_/          |");
-    println!(r"|                                           |");
+fn gen(root_path: &Path, seed: Option<u64>, rebuild: bool) -> Result<()> {
+    /* 古い実行結果が新しいテストケースと混ざらないよう，まとめて削除する */
+    init(root_path.to_path_buf())?;
+    let program = Program::find(root_path, "generator")?;
+    compile_with_cache(root_path, &program, rebuild)?;
+    generator(root_path.to_path_buf(), seed)?;
+
+    let testcase_dir_path = root_path.join("testcase");
+    let testcase_count = MyFileIO::get_path_list(testcase_dir_path.clone())?.len();
     println!(
-        "|                  v{}                   |",
-        env!("CARGO_PKG_VERSION")
+        "{} {} testcases are created in {}",
+        PrintColorize::print_cyan(String::from("[ generator ]")),
+        testcase_count,
+        testcase_dir_path.to_string_lossy()
     );
-    println!(r"| Competitive Programming Stress Test Tools |");
-    println!(r"---------------------------------------------");
     Ok(())
 }
 
 /**
- * プログラムの初期化
- * @param test_path テストディレクトリへの絶対パス
+ * testcase/のテストケースでsmart/stupidを実行し，実行結果を保存 (比較は行わない)
+ * @param root_path テストディレクトリへの絶対パス
+ * @param program_name 実行するプログラム (Noneの場合はsmartとstupid)
+ * @param run_options テストの実行方法
  * @return 正常終了の有無
  */
-fn init(test_path: PathBuf) -> Result<()> {
-    /* 空ディレクトリの生成 */
-    MyFileIO::make_init_dir(test_path.clone())?;
-
-    /* 不要なファイルを削除 */
-    let testcase_path = test_path.clone().join("testcase");
-    MyFileIO::file_clean(testcase_path)?;
-
-    let output_smart_path = test_path.clone().join("cpstt_out/smart");
-    MyFileIO::file_clean(output_smart_path)?;
-
-    let output_stupid_path = test_path.clone().join("cpstt_out/stupid");
-    MyFileIO::file_clean(output_stupid_path)?;
+fn exec(root_path: &Path, program_name: Option<&str>, run_options: &RunOptions) -> Result<()> {
+    /* 比較を行わないため，判定結果を使うオプションは指定できない */
+    if run_options.fail_fast || run_options.junit.is_some() {
+        PrintError::print_error(String::from(
+            "--fail-fast and --junit can not be used with cpstt exec because it does not judge \
+             the outputs. Use --junit with cpstt compare",
+        ));
+        bail!("Some Error is occurred!");
+    }
+    MyFileIO::make_init_dir(root_path.to_path_buf())?;
+    let program_names = match program_name {
+        Some(program_name) => vec![program_name],
+        None => vec!["smart", "stupid"],
+    };
+    let mut programs = Vec::new();
+    for program_name in program_names {
+        let program = Program::find(root_path, program_name)?;
+        compile_with_cache(root_path, &program, run_options.rebuild)?;
+        programs.push(program);
+    }
+    let testcase_path_list = list_testcases(root_path, &run_options.only)?;
 
-    MyFileIO::file_clean(PathBuf::from(format!(
-        "{}/cpstt_out/bin/",
-        test_path.clone().to_string_lossy(),
-    )))?;
+    let print_lock = Mutex::new(());
+    let exec_testcase = |testcase: PathBuf| -> Result<()> {
+        let testcase_name = testcase.file_stem().unwrap().to_string_lossy().into_owned();
+        for program in &programs {
+            let result = exec_program(program, &testcase)
+                .unwrap_or_else(|e| ExecResult::no_output(e.to_string()));
+            save_output(root_path, &program.name, &testcase, &result)?;
+            let _lock = print_lock.lock().unwrap();
+            print_exec_result(&program.name, &testcase_name, &result);
+        }
+        Ok(())
+    };
+    for result in WorkerPool::map(run_options.jobs, testcase_path_list, exec_testcase) {
+        result?;
+    }
     Ok(())
 }
 
 /**
- * generatorを実行
- * @param generator_path テストディレクトリへの絶対パス
- * @return 正常終了の有無
+ * cpstt_out/に保存されたsmartとstupidの出力をテストケースごとに比較
+ * @param root_path テストディレクトリへの絶対パス
+ * @param run_options テストの実行方法
+ * @return 異常終了: エラー
+ *         正常終了: 判定結果の集計
  */
-fn generator(mut generator_path: PathBuf) -> Result<()> {
-    /* パスの作成 */
-    let generator_root_path = generator_path.clone();
-    generator_path.push("generator.cpp");
+fn compare(root_path: &Path, run_options: &RunOptions) -> Result<VerdictSummary> {
+    MyFileIO::make_init_dir(root_path.to_path_buf())?;
+    /* checkerがあれば出力の比較に使う */
+    let checker = Program::find_optional(root_path, "checker")?;
+    if let Some(checker) = &checker {
+        compile_with_cache(root_path, checker, run_options.rebuild)?;
+    }
+    let testcase_path_list = list_testcases(root_path, &run_options.only)?;
 
-    /* generatorを実行 */
-    let args = vec![String::from(generator_root_path.to_str().unwrap())];
-    let exec_output = exec_generator(generator_path.clone(), &args, &generator_root_path)?;
-    if SETTING.logging.dump_exe_result {
-        println!(
-            "{} is done.",
-            PrintColorize::print_cyan(String::from("[ generator ]"))
+    /* テストケースと実行結果の対応を確認する */
+    let problems = check_outputs(root_path, &list_testcases(root_path, &[])?)?;
+    for problem in &problems {
+        PrintError::print_warning(problem.clone());
+    }
+    if !problems.is_empty() {
+        PrintError::print_warning(format!(
+            "{} testcases in testcase/ and cpstt_out/ do not match. Run cpstt exec again",
+            problems.len()
+        ));
+    }
+
+    let finished_files = Mutex::new(0);
+    let compare_testcase = |testcase: PathBuf| -> Result<(Verdict, TestcaseReport)> {
+        let testcase_name = testcase.file_stem().unwrap().to_string_lossy().into_owned();
+        /* cpstt execで保存した実行状態を使う (出力が保存されていない場合は no output となる) */
+        let smart_path = output_path(root_path, "smart", &testcase);
+        let stupid_path = output_path(root_path, "stupid", &testcase);
+        let smart_report = read_exec_report(&smart_path)?;
+        let stupid_report = read_exec_report(&stupid_path)?;
+        let (verdict, culprit, message) = judge_testcase(
+            checker.as_ref(),
+            &testcase,
+            (&smart_path, Some(&smart_report.exec_status())),
+            (&stupid_path, Some(&stupid_report.exec_status())),
+        )?;
+
+        let mut finished_files = finished_files.lock().unwrap();
+        *finished_files += 1;
+        print_verdict(
+            &testcase_name,
+            (*finished_files, testcase_path_list.len()),
+            verdict,
+            culprit,
+            &message,
         );
-        println!("{}", exec_output);
-    } else {
-        println!(
-            "{} is done.",
-            PrintColorize::print_cyan(String::from("[ generator ]"))
+        if verdict == Verdict::WA {
+            print_wrong_answer(root_path, &testcase_name, &smart_path, &stupid_path)?;
+        }
+        let report = TestcaseReport::new(
+            &testcase_name,
+            &testcase,
+            verdict,
+            !culprit.is_empty(),
+            &message,
+            smart_report,
+            stupid_report,
         );
+        Ok((verdict, report))
+    };
+    let results = WorkerPool::map(
+        run_options.jobs,
+        testcase_path_list.clone(),
+        compare_testcase,
+    );
+
+    let mut summary = VerdictSummary::default();
+    let mut testcases = Vec::new();
+    for result in results {
+        let (verdict, testcase) = result?;
+        summary.add(&testcase.name, verdict);
+        testcases.push(testcase);
     }
-    Ok(())
+    print_summary(&summary);
+
+    /* 結果を cpstt_out/report.json に保存 */
+    let report = make_report(root_path, None, run_options, &summary, testcases, Vec::new())?;
+    report.write(&root_path.join("cpstt_out/report.json"))?;
+    if let Some(junit_path) = &run_options.junit {
+        JUnit::write(&report, junit_path)?;
+    }
+    Ok(summary)
 }
 
 /**
- * smart/stupidを実行
- * @param program_path テストディレクトリへの絶対パス
- * @param testcase_paths テストケースのパス一覧
- * @param program_type smart or stupid
- * @return 正常終了の有無
+ * テストケースとsmart/stupidの実行結果の対応を確認する
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase_paths テストケースへのパスのリスト
+ * @return 異常終了: エラー
+ *         正常終了: 対応しないテストケースごとの問題点
  */
-fn exec_user_program(
-    mut program_path: PathBuf,
-    testcase_paths: &Vec<PathBuf>,
-    program_type: String,
-) -> Result<()> {
-    let program_root_path = program_path.clone();
-    program_path.push(format!("{}.cpp", &program_type));
+fn check_outputs(root_path: &Path, testcase_paths: &[PathBuf]) -> Result<Vec<String>> {
+    let stems = |paths: Vec<PathBuf>, extensions: &[&str]| -> BTreeSet<String> {
+        paths
+            .iter()
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extensions.iter().any(|e| extension == *e))
+            })
+            .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+            .collect()
+    };
+    let testcases = stems(testcase_paths.to_vec(), &["in"]);
+    /* 実行結果 (.diff) か，実行できなかったことを表す実行状態 (.status) があれば実行済みとする */
+    let mut outputs = Vec::new();
+    for program_type in ["smart", "stupid"] {
+        let output_dir = root_path.join("cpstt_out").join(program_type);
+        let output_paths = MyFileIO::get_path_list(output_dir)?;
+        outputs.push((program_type, stems(output_paths, &["diff", "status"])));
+    }
 
-    /* C++プログラムを全て並列実行 */
-    let mut handles = Vec::new();
-    let finished_files = Arc::new(Mutex::new(0));
-
-    for testcase in testcase_paths {
-        /* サブスレッドへデータを渡す */
-        let (sender_data, receiver_data) = mpsc::channel();
-        struct SenderData {
-            program_path: PathBuf,
-            program_root_path: PathBuf,
-            testcase: PathBuf,
-            testcase_len: usize,
-            program_type: String,
-        }
-        let send_data = SenderData {
-            program_path: program_path.clone(),
-            program_root_path: program_root_path.clone(),
-            testcase: testcase.clone(),
-            testcase_len: testcase_paths.len(),
-            program_type: program_type.clone(),
+    let mut all_stems = testcases.clone();
+    for (_, output_stems) in &outputs {
+        all_stems.extend(output_stems.iter().cloned());
+    }
+    let mut problems = Vec::new();
+    for stem in &all_stems {
+        let programs = |contains: bool| -> Vec<&str> {
+            outputs
+                .iter()
+                .filter(|(_, output_stems)| output_stems.contains(stem) == contains)
+                .map(|(program_type, _)| *program_type)
+                .collect()
         };
-        let finished_files_ref = finished_files.clone();
-        sender_data.send(send_data).unwrap();
+        let (found, missing) = (programs(true), programs(false));
+        if !testcases.contains(stem) {
+            problems.push(format!("{}: output of {} has no testcase", stem, found.join(", ")));
+        } else if !missing.is_empty() {
+            problems.push(format!("{}: no output from {}", stem, missing.join(", ")));
+        }
+    }
+    Ok(problems)
+}
 
-        handles.push(thread::spawn(move || {
-            /* メインスレッドから受け取り */
-            let rcv_data = receiver_data.recv().unwrap();
-            let args = vec![
-                String::from("<"),
-                String::from(rcv_data.testcase.to_str().unwrap()),
-            ];
-            /* C++実行 */
-            let (exec_output, exec_time, is_tle) = exec_cpp_program(
-                rcv_data.program_path.clone(),
-                &args,
-                &rcv_data.program_root_path,
-            )
-            .unwrap();
-            /* 結果を出力 */
-            let mut finished_files = finished_files_ref.lock().unwrap();
-            *finished_files += 1;
-            let crr_finished_file = *finished_files;
-            println!(
-                "{} {} ({:2}/{:2}) is {}. ({}.{:03} sec)",
-                PrintColorize::print_cyan(format!("[ {} ]", rcv_data.program_type)),
-                rcv_data.testcase.file_stem().unwrap().to_string_lossy(),
-                crr_finished_file,
-                rcv_data.testcase_len,
-                is_tle,
-                exec_time.as_secs(),
-                exec_time.subsec_nanos() / 1_000_000
-            );
-            if SETTING.logging.dump_exe_result {
-                let max_len = SETTING.execution.max_output_len as usize;
-                if exec_output.len() < max_len {
-                    /* 実行結果の文字列が短い場合 */
-                    println!("{}", exec_output);
-                } else {
-                    /* 実行結果の文字列が長い場合 */
-                    let exec_output_format =
-                        exec_output.replace("\n", "\x1b[33m\\n\x1b[m").replacen(
-                            "\x1b[33m\\n\x1b[m",
-                            "\n",
-                            (SETTING.execution.max_output_line - 1) as usize,
-                        );
-                    println!(
-                        "Output data is too large. (content-size: {})",
-                        exec_output.len()
-                    );
-                    let end = exec_output_format.char_indices().nth(max_len).unwrap().0;
-                    let sliced_output = &exec_output_format[0..end];
-                    println!("{}\x1b[m\n......\n", &sliced_output);
-                }
-            }
-            /* 実行結果をファイル書き込み */
-            let mut output_path = rcv_data.program_root_path.clone().join(format!("cpstt_out/{}", rcv_data.program_type)).join(rcv_data.testcase.file_name().unwrap().to_str().unwrap());
-            output_path.set_extension("diff");
-            MyFileIO::write_file(&output_path, &exec_output).unwrap();
-        }));
+/**
+ * テストケースと実行結果を削除
+ * @param root_path テストディレクトリへの絶対パス
+ * @param all コンパイルしたバイナリファイルとビルドキャッシュも削除するか
+ * @return 正常終了の有無
+ */
+fn clean(root_path: &Path, all: bool) -> Result<()> {
+    MyFileIO::make_init_dir(root_path.to_path_buf())?;
+    clean_outputs(root_path.to_path_buf())?;
+    for report_name in ["report.json", "report.html"] {
+        let report_path = root_path.join("cpstt_out").join(report_name);
+        if report_path.is_file() {
+            fs::remove_file(report_path)?;
+        }
     }
-    for handle in handles {
-        let _ = handle.join();
+    if all {
+        /* 言語によってはプログラムごとのディレクトリがあるため，ディレクトリごと削除する */
+        let bin_path = root_path.join("cpstt_out/bin");
+        fs::remove_dir_all(&bin_path)?;
+        MyFileIO::make_dir(bin_path)?;
     }
+    println!(
+        "{} {} is cleaned.",
+        PrintColorize::print_cyan(String::from("[ clean ]")),
+        root_path.to_string_lossy()
+    );
     Ok(())
 }
 
 /**
- * C++のファイルを指定し，そのプログラムをコンパイルする
- * @param cpp_path C++ファイルへのパス
- * @param id C++プログラムID
- * @return 異常終了: エラー
- *         正常終了: 実行結果の文字列
+ * テンプレートからgenerator/smart/stupid/settings.tomlと必要なディレクトリを作成
+ * @param dir --dirで指定したディレクトリ
+ * @param init_opts initサブコマンドのオプション
+ * @return 正常終了の有無
  */
-fn compile(cpp_path: &PathBuf, id: String) -> Result<(), anyhow::Error> {
-    let mut dir_root_path = cpp_path.clone();
-    dir_root_path.pop();
-    let compile_output = Command::new("g++")
-        .args(&[
-            "-std=c++1z",
-            "-O3",
-            "-fsanitize=undefined",
-            "-I",
-            ".",
-            "-o",
-            &(format!("cpstt_out/bin/{}", id)),
-            cpp_path.to_str().unwrap(),
-        ])
-        .current_dir(dir_root_path.to_str().unwrap())
-        .output()
-        .expect("Failed to compile C++ program");
+fn init_dir(dir: Option<&Path>, init_opts: &InitOpts) -> Result<()> {
+    /* 親ディレクトリのsettings.tomlは探さず，指定したディレクトリかカレントディレクトリに作成する */
+    let current_dir = std::env::current_dir()?;
+    let root_path = match init_opts.dir.as_deref().or(dir) {
+        Some(dir) => current_dir.join(dir),
+        None => current_dir,
+    };
+    fs::create_dir_all(&root_path)?;
+    MyFileIO::make_init_dir(root_path.clone())?;
 
-    let compile_stderr = String::from_utf8_lossy(&compile_output.stderr);
-    if compile_stderr != String::from("") {
-        eprintln!("{}", compile_stderr);
-        PrintError::print_error(String::from("It seems compile error"));
-        bail!("Some Error is occurred!");
+    for (file_name, content) in Templates::files(&init_opts.template)? {
+        let file_path = root_path.join(file_name);
+        /* 書きかけのファイルを消さないよう，既存のファイルは--forceが無ければ残す */
+        if file_path.exists() && !init_opts.force {
+            println!(
+                "{} {} already exists. Skipped.",
+                PrintColorize::print_cyan(String::from("[ init ]")),
+                file_path.to_string_lossy()
+            );
+            continue;
+        }
+        MyFileIO::write_file(&file_path, &String::from(content))?;
+        println!(
+            "{} {} is created.",
+            PrintColorize::print_cyan(String::from("[ init ]")),
+            file_path.to_string_lossy()
+        );
     }
+    println!(
+        "{} {} is initialized with the {} template.",
+        PrintColorize::print_cyan(String::from("[ init ]")),
+        root_path.to_string_lossy(),
+        init_opts.template
+    );
     Ok(())
 }
 
 /**
- * C++のファイルを指定し，そのプログラムを実行する
- * @param cpp_path C++ファイルへのパス
- * @param exec_args C++実行形式ファイルのコマンドライン引数
- * @param root_path C++ファイルがあるディレクトリへのパス
+ * testcase/のテストケースの一覧を取得
+ * @param root_path テストディレクトリへの絶対パス
+ * @param only 実行するテストケース名のパターン (空の場合は全て)
  * @return 異常終了: エラー
- *         正常終了: 実行結果の文字列
+ *         正常終了: テストケースへのパスの一覧
  */
-fn exec_generator(
-    cpp_path: PathBuf,
-    exec_args: &Vec<String>,
-    root_path: &PathBuf,
-) -> Result<String> {
-    let mut compile_path = PathBuf::from("generator");
-    compile_path.set_extension(&SETTING.execution.bin_extension);
-    compile(
-        &cpp_path,
-        String::from(compile_path.file_name().unwrap().to_string_lossy()),
-    )?;
-    let mut output_path = PathBuf::from(format!(
-        "{}/cpstt_out/bin/generator",
-        &root_path.to_str().unwrap()
-    ));
-    output_path.set_extension(&SETTING.execution.bin_extension);
-    let exec_output = Command::new(&output_path.to_str().unwrap())
-        .args(exec_args)
-        .output()
-        .expect("Failed to execution C++ program");
-
-    let exec_stdout = String::from_utf8_lossy(&exec_output.stdout);
-    let exec_stderr = String::from_utf8_lossy(&exec_output.stderr);
-    if exec_stderr != String::from("") {
-        eprintln!("{}", exec_stderr);
-        PrintError::print_error(String::from("It seems execution error"));
+fn list_testcases(root_path: &Path, only: &[String]) -> Result<Vec<PathBuf>> {
+    let testcase_dir_path = root_path.join("testcase");
+    let testcase_path_list = MyFileIO::get_path_list(testcase_dir_path.clone())?;
+    if testcase_path_list.is_empty() {
+        PrintError::print_error(format!(
+            "There is no testcase in {}. Run cpstt gen first",
+            testcase_dir_path.to_string_lossy()
+        ));
         bail!("Some Error is occurred!");
     }
-    Ok(exec_stdout.into_owned())
+    filter_testcases(testcase_path_list, only)
 }
 
 /**
- * C++のファイルを指定し，そのプログラムを実行する
- * @param cpp_path C++ファイルへのパス
- * @param exec_args C++実行形式ファイルのコマンドライン引数
- * @param root_path C++ファイルがあるディレクトリへのパス
+ * --onlyのパターンに一致するテストケースのみを残す
+ * @param testcase_path_list テストケースへのパスの一覧
+ * @param only テストケース名のパターン (空の場合は全て)
  * @return 異常終了: エラー
- *         正常終了: 実行結果の文字列
+ *         正常終了: 一致したテストケースへのパスの一覧
  */
-fn exec_cpp_program(
-    cpp_path: PathBuf,
-    exec_args: &Vec<String>,
-    root_path: &PathBuf,
-) -> Result<(String, Duration, String)> {
-    /* 乱数生成 */
-    let mut bin_path = PathBuf::from(PathBuf::from(exec_args[1].clone()).file_name().unwrap());
-    bin_path.set_extension(&SETTING.execution.bin_extension);
-    let id = String::from(bin_path.file_name().unwrap().to_string_lossy());
-    compile(
-        &cpp_path,
-        String::from(bin_path.file_name().unwrap().to_string_lossy()),
-    )?;
-
-    let exec_cat = Command::new("cat")
-        .args(&[exec_args[1].clone()])
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to load testcase");
-
-    /* メインスレッドとサブスレッドのデータ送受信チャンネル */
-    let (sender_root_path, receiver_root_path) = mpsc::channel();
-    sender_root_path.send(root_path.clone()).unwrap();
-    let (sender_args, receiver_args) = mpsc::channel();
-    sender_args.send(exec_args.clone()).unwrap();
-    let (sender_id, receiver_id) = mpsc::channel();
-    sender_id.send(id.clone()).unwrap();
-
-    /* サブスレッドからのデータ書き込み先 */
-    let output = Arc::new(Mutex::new(vec![String::from(""), String::from("")]));
-    let output_ref = output.clone();
-    let exec_time = Arc::new(Mutex::new(Duration::new(0, 0)));
-    let exec_ref = exec_time.clone();
-
-    /* 時間計測開始 */
-    let start = Instant::now();
-
-    /* C++プログラムの実行 */
-    thread::spawn(move || {
-        let root_path = receiver_root_path.recv().unwrap();
-        let exec_args = receiver_args.recv().unwrap();
-        let id = receiver_id.recv().unwrap();
-        let exec_cpp = Command::new(format!(
-            "{}/cpstt_out/bin/{}",
-            root_path.to_str().unwrap(),
-            id
-        ))
-        .args(exec_args)
-        .stdin(unsafe { Stdio::from_raw_fd(exec_cat.stdout.as_ref().unwrap().as_raw_fd()) })
-        .output()
-        .expect("Failed to execution C++ program");
-        /* 実行結果の書き込み */
-        let mut output = output_ref.lock().unwrap();
-        output[0] = String::from_utf8_lossy(&exec_cpp.stdout).into_owned();
-        output[1] = String::from_utf8_lossy(&exec_cpp.stderr).into_owned();
-        /* 実行時間の書き込み */
-        let mut exec_time = exec_ref.lock().unwrap();
-        *exec_time = start.elapsed();
-    });
-
-    /* スリープの実行 */
-    let handle2 = thread::spawn(move || {
-        thread::sleep(Duration::from_millis(SETTING.execution.time_limit));
-    });
-    let _ = handle2.join();
-
-    let end = *exec_time.lock().unwrap();
-    let std_out_err = (*output.lock().unwrap()).clone();
-
-    /* 実行時エラーの処理 */
-    if std_out_err[1] != String::from("") {
-        eprintln!("{}", std_out_err[1]);
+fn filter_testcases(testcase_path_list: Vec<PathBuf>, only: &[String]) -> Result<Vec<PathBuf>> {
+    if only.is_empty() {
+        return Ok(testcase_path_list);
+    }
+    let filtered: Vec<PathBuf> = testcase_path_list
+        .into_iter()
+        .filter(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            only.iter().any(|pattern| glob_match(pattern, &name))
+        })
+        .collect();
+    if filtered.is_empty() {
         PrintError::print_error(format!(
-            "It seems execution error [{}]",
-            cpp_path.to_str().unwrap()
+            "There is no testcase matching {}",
+            only.join(", ")
         ));
         bail!("Some Error is occurred!");
     }
-
-    /* 実行形式ファイルの削除 */
-    // let _exec_cat = Command::new("rm")
-    //     .args(&[format!(
-    //         "{}/cpstt_out/bin/{}",
-    //         root_path.clone().to_string_lossy(),
-    //         id.clone()
-    //     )])
-    //     .spawn()
-    //     .expect("Failed to remove execfile");
-
-    /* TLE判定 */
-    let is_tle = if end != Duration::new(0, 0) {
-        String::from("done")
-    } else {
-        PrintColorize::print_yellow(String::from("TLE"))
-    };
-    Ok((std_out_err[0].clone(), end, is_tle))
+    Ok(filtered)
 }
 
 /**
- * smartとstupidの結果を比較する
- * @param root_path 本プログラムへの絶対パス
- * @return 異常終了: エラー
- *         正常終了: 実行結果の文字列
+ * ワイルドカード(*: 任意の文字列, ?: 任意の1文字)を含むパターンとの一致判定
+ * @param pattern パターン
+ * @param text 判定する文字列
+ * @return 一致すればtrue
  */
-fn compare_result(root_path: PathBuf) -> Result<()> {
-    /* フォルダパスの生成 */
-    let smart_test_path = root_path.clone().join("cpstt_out/smart");
-    let stupid_test_path = root_path.clone().join("cpstt_out/stupid");
-    /* テストケースのパスの取得 */
-    let smart_test = MyFileIO::get_path_list(smart_test_path).unwrap();
-    let stupid_test = MyFileIO::get_path_list(stupid_test_path).unwrap();
-
-    let mut accepted = 0;
-    let mut wrong_answer = 0;
-
-    /* ファイルを読み込みながら比較 */
-    for (smart, stupid) in (smart_test.iter()).zip(stupid_test.iter()) {
-        /* ファイルを読み込み */
-        let smart_content = MyFileIO::read_file(String::from(smart.to_str().unwrap()))?;
-        let stupid_content = MyFileIO::read_file(String::from(stupid.to_str().unwrap()))?;
-        /* 比較 */
-        if smart_content == stupid_content {
-            accepted += 1;
-            println!(
-                "{} {}: {}",
-                PrintColorize::print_cyan(String::from("[ test ]")),
-                PrintColorize::print_green(String::from("AC")),
-                smart.file_stem().unwrap().to_str().unwrap()
-            );
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    /* 最後の*の位置と，その*に対応させたtextの位置 (不一致なら*を1文字伸ばしてやり直す) */
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
         } else {
-            wrong_answer += 1;
-            println!(
-                "{} {}: {}",
-                PrintColorize::print_cyan(String::from("[ test ]")),
-                PrintColorize::print_yellow(String::from("WA")),
-                smart.file_stem().unwrap().to_str().unwrap()
-            );
+            return false;
         }
     }
-    /* 結果を出力 */
-    println!(
-        "{} {}: {}, {}: {} (testcase: {})",
-        PrintColorize::print_cyan(String::from("[ result ]")),
-        PrintColorize::print_green(String::from("AC")),
-        accepted,
-        PrintColorize::print_yellow(String::from("WA")),
-        wrong_answer,
-        accepted + wrong_answer
-    );
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/**
+ * 実際に使われる設定の値と，各項目の読み込み元を表示
+ * @param root_path テストディレクトリへの絶対パス
+ * @return 正常終了の有無
+ */
+fn show_config(root_path: &Path) -> Result<()> {
+    print_config_files(root_path);
+    let loaded = Config::load_or_report(root_path, &MyFileIO::get_override())?;
+    println!();
+    print_config_table(&loaded.value, "", &loaded.sources);
     Ok(())
 }
 
+/**
+ * 設定ファイル・環境変数を検証し，問題があれば全て表示
+ * @param root_path テストディレクトリへの絶対パス
+ * @return 異常終了: 設定に問題がある
+ *         正常終了: 設定に問題が無い
+ */
+fn validate_config(root_path: &Path) -> Result<()> {
+    print_config_files(root_path);
+    Config::load_or_report(root_path, &MyFileIO::get_override())?;
+    println!(
+        "{} settings are valid",
+        PrintColorize::print_green(String::from("[ config ]"))
+    );
+    Ok(())
+}
+
+/**
+ * 読み込む設定ファイルへのパスを表示
+ * @param root_path テストディレクトリへの絶対パス
+ */
+fn print_config_files(root_path: &Path) {
+    let project_path = root_path.join("settings.toml");
+    let config_files = [
+        ("global", Config::global_path()),
+        ("project", Some(project_path)),
+    ];
+    for (name, path) in config_files.iter() {
+        let status = match path {
+            Some(path) if path.is_file() => path.to_string_lossy().into_owned(),
+            Some(path) => format!("{} (not found)", path.to_string_lossy()),
+            None => String::from("(not found)"),
+        };
+        println!(
+            "{} {}: {}",
+            PrintColorize::print_cyan(String::from("[ config ]")),
+            name,
+            status
+        );
+    }
+}
+
+/**
+ * 設定のテーブルをTOML形式で，各項目の読み込み元と共に出力
+ * @param value テーブル
+ * @param path テーブルの位置 (最上位の場合は空文字列)
+ * @param sources 項目ごとの読み込み元
+ */
+fn print_config_table(
+    value: &toml::Value,
+    path: &str,
+    sources: &std::collections::BTreeMap<String, ConfigSource>,
+) {
+    let table = match value.as_table() {
+        Some(table) => table,
+        None => return,
+    };
+    let key_path = |key: &str| {
+        if path.is_empty() {
+            String::from(key)
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    /* テーブル以外の項目を先に出力し，その後に子のテーブルを出力する */
+    let items: Vec<(String, String)> = table
+        .iter()
+        .filter(|(_, value)| !value.is_table())
+        .map(|(key, value)| (format!("{} = {}", key, value), key_path(key)))
+        .collect();
+    if !items.is_empty() {
+        println!("[{}]", path);
+        let width = items.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+        for (line, item_path) in items {
+            let source = sources
+                .get(&item_path)
+                .map_or_else(String::new, |source| source.to_string());
+            println!("{:width$}  # {}", line, source, width = width);
+        }
+        println!();
+    }
+    for (key, value) in table.iter().filter(|(_, value)| value.is_table()) {
+        print_config_table(value, &key_path(key), sources);
+    }
+}
+
+/**
+ * 前回のテストの結果を表示，またはHTMLに出力
+ * @param root_path テストディレクトリへの絶対パス
+ * @param report_opts reportサブコマンドのオプション
+ * @return 正常終了の有無
+ */
+fn show_report(root_path: &Path, report_opts: &ReportOpts) -> Result<()> {
+    let report_path = root_path.join("cpstt_out/report.json");
+    if !report_path.is_file() {
+        PrintError::print_error(format!(
+            "{} is not found. Run cpstt first",
+            report_path.to_string_lossy()
+        ));
+        bail!("Some Error is occurred!");
+    }
+    let report = match Report::read(&report_path) {
+        Ok(report) if report.schema_version == SCHEMA_VERSION => report,
+        Ok(report) => {
+            PrintError::print_error(format!(
+                "{} has schema version {}, but this cpstt supports {}. Run cpstt again",
+                report_path.to_string_lossy(),
+                report.schema_version,
+                SCHEMA_VERSION
+            ));
+            bail!("Some Error is occurred!");
+        }
+        Err(e) => {
+            PrintError::print_error(format!(
+                "Failed to read {}: {}",
+                report_path.to_string_lossy(),
+                e
+            ));
+            bail!("Some Error is occurred!");
+        }
+    };
+    /* report.jsonの作成後にcpstt gen/execでテストケースや実行結果が更新されていれば警告する */
+    if let Some(newer_path) = find_newer_output(root_path, report.created_at)? {
+        /* commandが無い古いreport.jsonはサブコマンドを表示しない */
+        let command = format!("cpstt {}", report.command);
+        PrintError::print_warning(format!(
+            "{} was created by {} before {} was updated. Run cpstt compare to update it",
+            report_path.to_string_lossy(),
+            command.trim_end(),
+            newer_path.to_string_lossy()
+        ));
+    }
+
+    if report_opts.html {
+        let html_path = report_opts
+            .output
+            .clone()
+            .unwrap_or_else(|| root_path.join("cpstt_out/report.html"));
+        HtmlReport::write(&report, &html_path)?;
+        println!(
+            "{} {}",
+            PrintColorize::print_cyan(String::from("[ report ]")),
+            html_path.to_string_lossy()
+        );
+        return Ok(());
+    }
+
+    /* 各テストケースの判定結果と集計を表示 */
+    let mut summary = VerdictSummary::default();
+    for testcase in &report.testcases {
+        let verdict: Verdict = testcase.verdict.parse()?;
+        summary.add(&testcase.name, verdict);
+        println!(
+            "{} {}: {}{}{}",
+            PrintColorize::print_cyan(String::from("[ test ]")),
+            verdict.to_colored_string(),
+            testcase.name,
+            testcase
+                .culprit
+                .as_ref()
+                .filter(|culprit| culprit.as_str() == "stupid")
+                .map_or("", |_| " (stupid)"),
+            if testcase.message.is_empty() {
+                String::new()
+            } else {
+                format!(" - {}", testcase.message)
+            }
+        );
+    }
+    print_summary(&summary);
+    Ok(())
+}
+
+/**
+ * 指定した時刻より後に更新されたテストケースか実行結果を探す
+ * @param root_path テストディレクトリへの絶対パス
+ * @param created_at 時刻 (UNIX時間の秒)
+ * @return 異常終了: エラー
+ *         正常終了: 更新されたファイルへのパス (無い場合はNone)
+ */
+fn find_newer_output(root_path: &Path, created_at: u64) -> Result<Option<PathBuf>> {
+    for dir in ["testcase", "cpstt_out/smart", "cpstt_out/stupid"] {
+        let dir_path = root_path.join(dir);
+        if !dir_path.is_dir() {
+            continue;
+        }
+        for path in MyFileIO::get_path_list(dir_path)? {
+            let modified = fs::metadata(&path)?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs());
+            if modified > created_at {
+                return Ok(Some(path));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/**
+ * 最小化するテストケースのパスを解決
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase テストケース名またはファイルへのパス
+ * @return 異常終了: エラー
+ *         正常終了: テストケースへのパス
+ */
+fn find_testcase(root_path: &Path, testcase: &str) -> Result<PathBuf> {
+    let mut by_name = root_path.join("testcase").join(testcase);
+    if by_name.extension().is_none() {
+        by_name.set_extension("in");
+    }
+    for candidate in [PathBuf::from(testcase), by_name].iter() {
+        if candidate.is_file() {
+            return Ok(candidate.clone());
+        }
+    }
+    PrintError::print_error(format!("testcase {} is not found", testcase));
+    bail!("Some Error is occurred!");
+}
+
+/**
+ * validatorで全てのテストケースを並列に検証し，制約を満たすものだけを返す
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase_paths テストケースへのパスのリスト
+ * @param jobs 並列実行数
+ * @return 異常終了: エラー
+ *         正常終了: 制約を満たすテストケースへのパスのリスト
+ */
+fn validate_testcases(
+    root_path: &Path,
+    testcase_paths: Vec<PathBuf>,
+    jobs: usize,
+) -> Result<Vec<PathBuf>> {
+    let validator = match Program::find_optional(root_path, "validator")? {
+        Some(validator) => validator,
+        None => return Ok(testcase_paths),
+    };
+
+    let results: Vec<Result<(PathBuf, (bool, String))>> =
+        WorkerPool::map(jobs, testcase_paths, |testcase| {
+            validate(&validator, &testcase).map(|result| (testcase, result))
+        });
+
+    let total = results.len();
+    let mut valid_testcases = Vec::new();
+    for result in results {
+        let (testcase, (is_valid, message)) = result?;
+        if is_valid {
+            valid_testcases.push(testcase);
+        } else {
+            println!(
+                "{} {} is {}.{}",
+                PrintColorize::print_cyan(String::from("[ validator ]")),
+                testcase.file_stem().unwrap().to_string_lossy(),
+                PrintColorize::print_yellow(String::from("invalid")),
+                if message.is_empty() {
+                    message
+                } else {
+                    format!(" ({})", message)
+                }
+            );
+        }
+    }
+    if valid_testcases.len() < total {
+        PrintError::print_warning(format!(
+            "{} of {} testcases are invalid and excluded from the comparison",
+            total - valid_testcases.len(),
+            total
+        ));
+    }
+    Ok(valid_testcases)
+}
+
+/**
+ * validatorで入力が制約を満たすか検証する (testlib互換: 標準入力から読み，不正なら0以外で終了する)
+ * (時間内に終了しない場合やシグナルで終了した場合は不正な入力ではなくエラーとする)
+ * @param validator validatorのプログラム
+ * @param input_path 入力へのパス
+ * @return 異常終了: エラー
+ *         正常終了: (制約を満たせばtrue, validatorのメッセージ)
+ */
+fn validate(validator: &Program, input_path: &Path) -> Result<(bool, String)> {
+    let stdin = Stdio::from(File::open(input_path)?);
+    let (status, message) = exec_testlib_program(validator, &[], stdin, input_path)?;
+    Ok((status.success(), message))
+}
+
+/**
+ * テストケースを失敗し続ける範囲で最小化し，cpstt_out/shrink/ に保存
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase_path 最小化するテストケースへのパス
+ * @return 異常終了: エラー
+ *         正常終了: 正常終了の有無
+ */
+fn shrink(root_path: &Path, testcase_path: &Path) -> Result<()> {
+    let smart = Program::find(root_path, "smart")?;
+    let stupid = Program::find(root_path, "stupid")?;
+    let checker = Program::find_optional(root_path, "checker")?;
+    let validator = Program::find_optional(root_path, "validator")?;
+
+    /* 候補の入力と実行結果の保存先 */
+    let work_path = root_path.join("cpstt_out/shrink");
+    let candidate_path = work_path.join("candidate.in");
+    let smart_output_path = work_path.join("candidate_smart.diff");
+    let stupid_output_path = work_path.join("candidate_stupid.diff");
+
+    /* 候補の入力を判定 (validatorに弾かれた場合はNone) */
+    let judge_candidate = |input: &str| -> Result<Option<(Verdict, &'static str)>> {
+        MyFileIO::write_file(&candidate_path, &String::from(input))?;
+        if let Some(validator) = &validator {
+            if !validate(validator, &candidate_path)?.0 {
+                return Ok(None);
+            }
+        }
+        let smart_result = exec_program(&smart, &candidate_path)?;
+        let stupid_result = exec_program(&stupid, &candidate_path)?;
+        MyFileIO::write_file(&smart_output_path, &smart_result.stdout)?;
+        MyFileIO::write_file(&stupid_output_path, &stupid_result.stdout)?;
+        let (verdict, culprit, _) = judge_testcase(
+            checker.as_ref(),
+            &candidate_path,
+            (&smart_output_path, Some(&smart_result.status)),
+            (&stupid_output_path, Some(&stupid_result.status)),
+        )?;
+        Ok(Some((verdict, culprit)))
+    };
+
+    /* 元のテストケースと同じ判定になる入力のみを採用する */
+    let input = MyFileIO::read_file(String::from(testcase_path.to_str().unwrap()))?;
+    let target = match judge_candidate(&input)? {
+        None => {
+            PrintError::print_error(format!(
+                "{} is rejected by the validator",
+                testcase_path.to_string_lossy()
+            ));
+            bail!("Some Error is occurred!");
+        }
+        Some((Verdict::AC, _)) => {
+            PrintError::print_error(format!("{} does not fail", testcase_path.to_string_lossy()));
+            bail!("Some Error is occurred!");
+        }
+        Some(target) => target,
+    };
+    println!(
+        "{} {} ({}{}, {} bytes)",
+        PrintColorize::print_cyan(String::from("[ shrink ]")),
+        testcase_path.to_string_lossy(),
+        target.0.to_colored_string(),
+        target.1,
+        input.len()
+    );
+
+    let mut runs = 0;
+    let mut shortest = input.len();
+    let minimized = Shrinker::minimize(&input, &mut |candidate| {
+        runs += 1;
+        /* checker/validatorの異常や実行できない候補は再現しないものとして扱う */
+        let is_failing =
+            matches!(judge_candidate(candidate), Ok(Some(verdict)) if verdict == target);
+        if is_failing && candidate.len() < shortest {
+            shortest = candidate.len();
+            println!(
+                "{} {} bytes",
+                PrintColorize::print_cyan(String::from("[ shrink ]")),
+                candidate.len()
+            );
+        }
+        Ok(is_failing)
+    })?;
+
+    /* 最小化したテストケースを保存 */
+    let mut output_path = work_path.join(testcase_path.file_stem().unwrap());
+    output_path.set_extension("in");
+    MyFileIO::write_file(&output_path, &minimized)?;
+    println!(
+        "{} {} bytes -> {} bytes ({} runs). Saved to {}",
+        PrintColorize::print_cyan(String::from("[ shrink ]")),
+        input.len(),
+        minimized.len(),
+        runs,
+        output_path.to_string_lossy()
+    );
+    print_truncated(&minimized);
+    Ok(())
+}
+
+/**
+ * CPSTTのロゴを出力
+ * @param path テストディレクトリへの絶対パス
+ * @return 正常終了の有無
+ */
+fn print_logo() -> Result<()> {
+    println!(r"---------------------------------------------");
+    println!(r"|         __________  _________________     |");
+    println!(r"|        / ____/ __ \/ ___/_  __/_  __/     |");
+    println!(r"|       / /   / /_/ /\__ \ / /   / /        |");
+    println!(r"|      / /___/ ____/___/ // /   / /         |");
+    println!(r"|      \____/_/    /____//_/   /_/          |");
+    println!(r"|                                           |");
+    println!(
+        "|                  v{}                   |",
+        env!("CARGO_PKG_VERSION")
+    );
+    println!(r"| Competitive Programming Stress Test Tools |");
+    println!(r"---------------------------------------------");
+    Ok(())
+}
+
+/**
+ * プログラムの初期化
+ * @param test_path テストディレクトリへの絶対パス
+ * @return 正常終了の有無
+ */
+fn init(test_path: PathBuf) -> Result<()> {
+    /* 空ディレクトリの生成 */
+    MyFileIO::make_init_dir(test_path.clone())?;
+
+    /* 不要なファイルを削除 (cpstt_out/bin/はキャッシュとして残す) */
+    clean_outputs(test_path)?;
+    Ok(())
+}
+
+/**
+ * テストケースと実行結果を削除
+ * @param test_path テストディレクトリへの絶対パス
+ * @return 正常終了の有無
+ */
+fn clean_outputs(test_path: PathBuf) -> Result<()> {
+    let testcase_path = test_path.clone().join("testcase");
+    MyFileIO::file_clean(testcase_path)?;
+
+    let output_smart_path = test_path.clone().join("cpstt_out/smart");
+    MyFileIO::file_clean(output_smart_path)?;
+
+    let output_stupid_path = test_path.clone().join("cpstt_out/stupid");
+    MyFileIO::file_clean(output_stupid_path)?;
+
+    let output_diff_path = test_path.clone().join("cpstt_out/diff");
+    MyFileIO::file_clean(output_diff_path)?;
+
+    let output_shrink_path = test_path.clone().join("cpstt_out/shrink");
+    MyFileIO::file_clean(output_shrink_path)?;
+    Ok(())
+}
+
+/**
+ * generator/smart/stupid(とcheckerがあればchecker)を並列にコンパイル
+ * @param root_path テストディレクトリへの絶対パス
+ * @param rebuild キャッシュを無視して再コンパイルするか
+ * @return 異常終了: エラー
+ *         正常終了: 正常終了の有無
+ */
+fn compile_all(root_path: PathBuf, rebuild: bool) -> Result<()> {
+    let mut programs = Vec::new();
+    for name in ["generator", "smart", "stupid"] {
+        programs.push(Program::find(&root_path, name)?);
+    }
+    for name in ["checker", "validator"] {
+        if let Some(program) = Program::find_optional(&root_path, name)? {
+            programs.push(program);
+        }
+    }
+
+    let mut handles = Vec::new();
+    for program in programs {
+        let root_path = root_path.clone();
+        handles.push(thread::spawn(move || {
+            compile_with_cache(&root_path, &program, rebuild)
+        }));
+    }
+    /* 全てのコンパイルが終わるのを待ってからエラーを返す */
+    let results: Vec<Result<()>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    for result in results {
+        result?;
+    }
+    Ok(())
+}
+
+/**
+ * ビルドキャッシュが無効な場合のみコンパイル
+ * @param root_path テストディレクトリへの絶対パス
+ * @param program コンパイルするプログラム
+ * @param rebuild キャッシュを無視して再コンパイルするか
+ * @return 異常終了: エラー
+ *         正常終了: 正常終了の有無
+ */
+fn compile_with_cache(root_path: &Path, program: &Program, rebuild: bool) -> Result<()> {
+    let compile_command = match program.compile_command() {
+        Some(compile_command) => compile_command,
+        None => {
+            /* インタプリタ言語はコンパイル不要 */
+            println!(
+                "{} {} does not need to be compiled.",
+                PrintColorize::print_cyan(String::from("[ compile ]")),
+                program.name
+            );
+            return Ok(());
+        }
+    };
+    let key = BuildCache::compute_key(
+        &program.source_path,
+        root_path,
+        &compile_command[0],
+        &compile_command[1..],
+    )?;
+
+    if !rebuild && BuildCache::is_fresh(&program.artifact_path, &key) {
+        println!(
+            "{} {} is up to date.",
+            PrintColorize::print_cyan(String::from("[ compile ]")),
+            program.name
+        );
+        return Ok(());
+    }
+
+    compile(program)?;
+    BuildCache::store(&program.artifact_path, &key)?;
+    println!(
+        "{} {} is done.",
+        PrintColorize::print_cyan(String::from("[ compile ]")),
+        program.name
+    );
+    Ok(())
+}
+
+/**
+ * generatorを実行
+ * @param generator_path テストディレクトリへの絶対パス
+ * @param seed generatorの第2引数として渡すシード値
+ * @return 正常終了の有無
+ */
+fn generator(generator_root_path: PathBuf, seed: Option<u64>) -> Result<()> {
+    /* generatorを実行 */
+    let mut args = vec![String::from(generator_root_path.to_str().unwrap())];
+    if let Some(seed) = seed {
+        args.push(seed.to_string());
+    }
+    let exec_output = exec_generator(&args, &generator_root_path)?;
+    if SETTING.logging.dump_exe_result {
+        println!(
+            "{} is done.",
+            PrintColorize::print_cyan(String::from("[ generator ]"))
+        );
+        println!("{}", exec_output);
+    } else {
+        println!(
+            "{} is done.",
+            PrintColorize::print_cyan(String::from("[ generator ]"))
+        );
+    }
+    Ok(())
+}
+
+/**
+ * テストケースごとに smart→stupid→比較 を行い，判定結果をすぐに出力する
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase_paths テストケースのパス一覧
+ * @param jobs 並列実行数
+ * @param fail_fast 最初にAC以外の判定が出たら残りのテストケースを実行しないか
+ * @return 異常終了: エラー
+ *         正常終了: (判定結果の集計, テストケースごとの判定結果, 実行しなかったテストケース名)
+ */
+fn exec_testcases(
+    root_path: &Path,
+    testcase_paths: &[PathBuf],
+    jobs: usize,
+    fail_fast: bool,
+) -> Result<(VerdictSummary, Vec<TestcaseReport>, Vec<String>)> {
+    let smart = Program::find(root_path, "smart")?;
+    let stupid = Program::find(root_path, "stupid")?;
+    /* checkerがあれば出力の比較に使う */
+    let checker = Program::find_optional(root_path, "checker")?;
+
+    let finished_files = Mutex::new(0);
+    let failed = AtomicBool::new(false);
+    let exec_testcase = |testcase: PathBuf| -> Result<Option<(Verdict, TestcaseReport)>> {
+        if fail_fast && failed.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let testcase_name = testcase.file_stem().unwrap().to_string_lossy().into_owned();
+
+        /* smartとstupidを実行 */
+        /* 実行できなかった場合は実行結果を保存せず，比較でREとなる */
+        let smart_result = exec_program(&smart, &testcase)
+            .unwrap_or_else(|e| ExecResult::no_output(e.to_string()));
+        let smart_path = save_output(root_path, "smart", &testcase, &smart_result)?;
+        let stupid_result = exec_program(&stupid, &testcase)
+            .unwrap_or_else(|e| ExecResult::no_output(e.to_string()));
+        let stupid_path = save_output(root_path, "stupid", &testcase, &stupid_result)?;
+
+        /* 比較 */
+        let (verdict, culprit, message) = judge_testcase(
+            checker.as_ref(),
+            &testcase,
+            (&smart_path, Some(&smart_result.status)),
+            (&stupid_path, Some(&stupid_result.status)),
+        )?;
+        if verdict != Verdict::AC {
+            failed.store(true, Ordering::SeqCst);
+        }
+
+        /* 他のテストケースの出力と混ざらないよう，まとめて出力する */
+        let mut finished_files = finished_files.lock().unwrap();
+        *finished_files += 1;
+        print_exec_result("smart", &testcase_name, &smart_result);
+        print_exec_result("stupid", &testcase_name, &stupid_result);
+        print_verdict(
+            &testcase_name,
+            (*finished_files, testcase_paths.len()),
+            verdict,
+            culprit,
+            &message,
+        );
+        if verdict == Verdict::WA {
+            print_wrong_answer(root_path, &testcase_name, &smart_path, &stupid_path)?;
+        }
+        let report = TestcaseReport::new(
+            &testcase_name,
+            &testcase,
+            verdict,
+            !culprit.is_empty(),
+            &message,
+            ExecReport::new(&smart_result, &smart_path),
+            ExecReport::new(&stupid_result, &stupid_path),
+        );
+        Ok(Some((verdict, report)))
+    };
+    let results = WorkerPool::map(jobs, testcase_paths.to_vec(), exec_testcase);
+
+    let mut summary = VerdictSummary::default();
+    let mut reports = Vec::new();
+    let mut skipped = Vec::new();
+    /* WorkerPool::mapは結果をテストケースの順に返す */
+    for (testcase, result) in testcase_paths.iter().zip(results) {
+        match result? {
+            Some((verdict, report)) => {
+                summary.add(&report.name, verdict);
+                reports.push(report);
+            }
+            None => skipped.push(testcase.file_stem().unwrap().to_string_lossy().into_owned()),
+        }
+    }
+    if !skipped.is_empty() {
+        println!(
+            "{} Stopped at the first failure. {} testcases were skipped.",
+            PrintColorize::print_cyan(String::from("[ test ]")),
+            skipped.len()
+        );
+    }
+    print_summary(&summary);
+    Ok((summary, reports, skipped))
+}
+
+/**
+ * smart/stupidの実行結果を cpstt_out/<smart or stupid>/ に保存
+ * @param root_path テストディレクトリへの絶対パス
+ * @param program_type smart or stupid
+ * @param testcase テストケースへのパス
+ * @param result 実行結果
+ * @return 異常終了: エラー
+ *         正常終了: 標準出力の保存先 (実行できなかった場合は保存しない)
+ */
+fn save_output(
+    root_path: &Path,
+    program_type: &str,
+    testcase: &Path,
+    result: &ExecResult,
+) -> Result<PathBuf> {
+    let output_path = output_path(root_path, program_type, testcase);
+    let stderr_path = output_path.with_extension("err");
+    if let ExecStatus::NoOutput(_) = result.status {
+        /* 前回の実行結果を比較しないよう削除する */
+        for path in [&output_path, &stderr_path] {
+            if path.is_file() {
+                fs::remove_file(path)?;
+            }
+        }
+    } else {
+        MyFileIO::write_file(&output_path, &result.stdout)?;
+        /* 標準エラー出力はファイルに保存する (前回の実行結果が残らないよう，空の場合は削除する) */
+        if !result.stderr.is_empty() {
+            MyFileIO::write_file(&stderr_path, &result.stderr)?;
+        } else if stderr_path.is_file() {
+            fs::remove_file(&stderr_path)?;
+        }
+    }
+    /* cpstt compareでTLE/MLE/REを判定し，report.jsonを作成できるよう，実行状態も保存する */
+    MyFileIO::write_file(
+        &output_path.with_extension("status"),
+        &serde_json::to_string(&ExecReport::new(result, &output_path))?,
+    )?;
+    Ok(output_path)
+}
+
+/**
+ * save_outputで保存した実行結果を読み込む
+ * @param output_path 標準出力の保存先
+ * @return 異常終了: エラー
+ *         正常終了: 実行結果 (保存されていない場合は標準出力の有無のみから作成する)
+ */
+fn read_exec_report(output_path: &Path) -> Result<ExecReport> {
+    let status_path = output_path.with_extension("status");
+    if !status_path.is_file() {
+        let status = if output_path.is_file() {
+            ExecStatus::Done
+        } else {
+            ExecStatus::NoOutput(String::from("not executed"))
+        };
+        let result = ExecResult {
+            status,
+            ..ExecResult::no_output(String::new())
+        };
+        return Ok(ExecReport::new(&result, output_path));
+    }
+    let content = MyFileIO::read_file(String::from(status_path.to_str().unwrap()))?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to read {}: {}", status_path.to_string_lossy(), e))
+}
+
+/**
+ * smart/stupidの標準出力の保存先
+ * @param root_path テストディレクトリへの絶対パス
+ * @param program_type smart or stupid
+ * @param testcase テストケースへのパス
+ * @return cpstt_out/<smart or stupid>/<テストケース名>.diff
+ */
+fn output_path(root_path: &Path, program_type: &str, testcase: &Path) -> PathBuf {
+    let mut output_path = root_path
+        .join(format!("cpstt_out/{}", program_type))
+        .join(testcase.file_name().unwrap());
+    output_path.set_extension("diff");
+    output_path
+}
+
+/**
+ * 1テストケースの判定結果を出力
+ * @param testcase_name テストケース名
+ * @param progress (判定済みのテストケース数, 全テストケース数)
+ * @param verdict 判定結果
+ * @param culprit 原因がstupidの場合は" (stupid)"
+ * @param message checkerのメッセージなど
+ */
+fn print_verdict(
+    testcase_name: &str,
+    progress: (usize, usize),
+    verdict: Verdict,
+    culprit: &str,
+    message: &str,
+) {
+    println!(
+        "{} {}: {} ({:2}/{:2}){}{}",
+        PrintColorize::print_cyan(String::from("[ test ]")),
+        verdict.to_colored_string(),
+        testcase_name,
+        progress.0,
+        progress.1,
+        culprit,
+        if message.is_empty() {
+            String::new()
+        } else {
+            format!(" - {}", message)
+        }
+    );
+}
+
+/**
+ * smart/stupidの実行状態・実行時間・使用メモリを出力
+ * @param program_type smart or stupid
+ * @param testcase_name テストケース名
+ * @param result 実行結果
+ */
+fn print_exec_result(program_type: &str, testcase_name: &str, result: &ExecResult) {
+    println!(
+        "{} {} is {}. ({}.{:03} sec, cpu {}.{:03} sec, {:.1} MB)",
+        PrintColorize::print_cyan(format!("[ {} ]", program_type)),
+        testcase_name,
+        result.status.to_colored_string(),
+        result.time.as_secs(),
+        result.time.subsec_millis(),
+        result.cpu_time.as_secs(),
+        result.cpu_time.subsec_millis(),
+        result.memory as f64 / 1024.0
+    );
+    if SETTING.logging.dump_exe_result {
+        print_truncated(&result.stdout);
+    }
+}
+
+/**
+ * 文字列をmax_output_len/max_output_lineで切り詰めて表示
+ * @param content 表示する文字列
+ */
+fn print_truncated(content: &str) {
+    let max_len = SETTING.execution.max_output_len as usize;
+    if content.chars().count() < max_len {
+        /* 文字列が短い場合 */
+        println!("{}", content);
+    } else {
+        /* 文字列が長い場合 */
+        let content_format = content.replace("\n", "\x1b[33m\\n\x1b[m").replacen(
+            "\x1b[33m\\n\x1b[m",
+            "\n",
+            (SETTING.execution.max_output_line - 1) as usize,
+        );
+        println!("Output data is too large. (content-size: {})", content.len());
+        let end = content_format
+            .char_indices()
+            .nth(max_len)
+            .map_or(content_format.len(), |(index, _)| index);
+        let sliced_output = &content_format[0..end];
+        println!("{}\x1b[m\n......\n", &sliced_output);
+    }
+}
+
+/**
+ * プログラムをコンパイルする
+ * @param program コンパイルするプログラム
+ * @return 異常終了: エラー
+ *         正常終了: 正常終了の有無
+ */
+fn compile(program: &Program) -> Result<(), anyhow::Error> {
+    let compile_command = program.compile_command().unwrap();
+    /* 出力ディレクトリに以前のクラスファイルなどが残らないよう削除しておく */
+    if program.artifact_path.is_dir() {
+        fs::remove_dir_all(&program.artifact_path)?;
+    }
+    let compile_output = match Command::new(&compile_command[0])
+        .args(&compile_command[1..])
+        .current_dir(program.source_path.parent().unwrap())
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            PrintError::print_error(format!(
+                "Failed to run compiler `{}` ({})",
+                compile_command[0], e
+            ));
+            bail!("Some Error is occurred!");
+        }
+    };
+
+    let compile_stderr = String::from_utf8_lossy(&compile_output.stderr);
+    if !compile_output.status.success() {
+        eprintln!("{}", compile_stderr);
+        PrintError::print_error(format!(
+            "It seems compile error [{}]",
+            program.source_path.to_str().unwrap()
+        ));
+        bail!("Some Error is occurred!");
+    }
+    if !compile_stderr.is_empty() {
+        /* 警告のみの場合は表示して続行する */
+        PrintError::print_warning(format!(
+            "{}\n{}",
+            program.source_path.to_str().unwrap(),
+            compile_stderr
+        ));
+    }
+    program.check_main_class()
+}
+
+/**
+ * コンパイル済みのgeneratorを実行する
+ * @param exec_args generatorのコマンドライン引数
+ * @param root_path generator.cppがあるディレクトリへのパス
+ * @return 異常終了: エラー
+ *         正常終了: 実行結果の文字列
+ */
+fn exec_generator(exec_args: &[String], root_path: &Path) -> Result<String> {
+    let program = Program::find(root_path, "generator")?;
+    let exec_output = program
+        .run_command()
+        .args(exec_args)
+        .output()
+        .map_err(|e| anyhow!("Failed to run {}: {}", program.name, e))?;
+
+    let exec_stdout = String::from_utf8_lossy(&exec_output.stdout);
+    let exec_stderr = String::from_utf8_lossy(&exec_output.stderr);
+    if !exec_stderr.is_empty() {
+        eprintln!("{}", exec_stderr);
+        PrintError::print_error(String::from("It seems execution error"));
+        bail!("Some Error is occurred!");
+    }
+    Ok(exec_stdout.into_owned())
+}
+
+/**
+ * テストケースを標準入力に与えてプログラムを実行する
+ * @param program 実行するプログラム
+ * @param testcase_path テストケースへのパス
+ * @return 異常終了: エラー
+ *         正常終了: 実行結果
+ */
+fn exec_program(program: &Program, testcase_path: &Path) -> Result<ExecResult> {
+    let testcase_file = File::open(testcase_path)?;
+    let memory_limit_kb = SETTING
+        .execution
+        .memory_limit
+        .checked_mul(1024)
+        .ok_or_else(|| anyhow!("memory_limit is too large"))?;
+
+    /* 時間計測開始 */
+    let start = Instant::now();
+
+    /* プログラムの実行 (TLE時にまとめてkillできるよう新しいプロセスグループで起動) */
+    let mut command = program.run_command();
+    command
+        .stdin(Stdio::from(testcase_file))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0);
+    let (memory_limit, cgroup_procs) =
+        MemoryLimit::apply(&mut command, memory_limit_kb, program.limits_data_segment())?;
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow!("Failed to run {}: {}", program.name, e))?;
+    drop(cgroup_procs);
+
+    /* パイプが詰まらないよう標準出力/標準エラー出力は別スレッドで読み込む */
+    let stdout_handle = spawn_pipe_reader(child.stdout.take().unwrap());
+    let stderr_handle = spawn_pipe_reader(child.stderr.take().unwrap());
+
+    /* 終了するかTime Limitを超えるまで待機 (CPU時間で判定する場合は実行時間の上限を緩める) */
+    let time_limit = Duration::from_millis(SETTING.execution.time_limit);
+    let wall_limit = match SETTING.execution.time_measure {
+        TimeMeasure::Wall => time_limit,
+        TimeMeasure::Cpu => time_limit * CPU_MODE_WALL_FACTOR,
+    };
+    let (end, wait_status, usage) =
+        wait_with_timeout(&mut child, start, wall_limit, memory_limit_kb)?;
+
+    let stdout = stdout_handle.join();
+    let stderr = stderr_handle.join();
+
+    /* 終了状態の判定 (ru_maxrssの単位はKB) */
+    let memory = usage.ru_maxrss.max(0) as u64;
+    let cpu_time = timeval_to_duration(usage.ru_utime) + timeval_to_duration(usage.ru_stime);
+    let exit_status = match wait_status {
+        WaitStatus::Exited(exit_status) => Some(exit_status),
+        _ => None,
+    };
+    let failed = !exit_status.is_some_and(|status| status.success());
+    let status = match wait_status {
+        WaitStatus::TimeLimitExceeded => ExecStatus::TLE,
+        WaitStatus::MemoryLimitExceeded => ExecStatus::MLE,
+        _ if SETTING.execution.time_measure == TimeMeasure::Cpu && cpu_time > time_limit => {
+            ExecStatus::TLE
+        }
+        /* cgroup/RLIMIT_DATAで制限された場合と，監視の間隔より短い間だけ超えた場合 */
+        _ if memory_limit.exceeded(failed, &stderr) => ExecStatus::MLE,
+        _ if memory_limit_kb > 0 && memory > memory_limit_kb => ExecStatus::MLE,
+        WaitStatus::Exited(exit_status) => ExecStatus::from_exit_status(exit_status),
+    };
+
+    Ok(ExecResult {
+        stdout,
+        stderr,
+        exit_code: exit_status.and_then(|status| status.code()),
+        signal: exit_status.and_then(|status| status.signal()),
+        time: end,
+        cpu_time,
+        memory,
+        status,
+    })
+}
+
+/**
+ * timevalをDurationに変換
+ * @param time rusageの時間
+ * @return 時間
+ */
+fn timeval_to_duration(time: libc::timeval) -> Duration {
+    Duration::from_secs(time.tv_sec.max(0) as u64)
+        + Duration::from_micros(time.tv_usec.max(0) as u64)
+}
+
+/**
+ * 実行中のプロセスの使用メモリ(RSS)を取得
+ * @param pid プロセスID
+ * @return 使用メモリ(KB) (取得できない場合は0)
+ */
+fn resident_memory(pid: libc::pid_t) -> u64 {
+    fs::read_to_string(format!("/proc/{}/status", pid))
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmRSS:"))
+                .and_then(|rss| rss.trim().trim_end_matches("kB").trim().parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/**
+ * パイプの内容を読み込むスレッド
+ */
+struct PipeReader {
+    /* 読み込んだ内容 */
+    buffer: Arc<Mutex<Vec<u8>>>,
+    /* パイプが閉じられたことの通知 */
+    finished: mpsc::Receiver<()>,
+}
+
+impl PipeReader {
+    /**
+     * パイプが閉じられるまで (最大PIPE_JOIN_TIMEOUT) 待ち，読み込んだ内容を返す
+     * (プロセスグループ外に逃れた孫プロセスがパイプを保持し続けても待ち続けない)
+     * @return 読み込んだ文字列
+     */
+    fn join(self) -> String {
+        let _ = self.finished.recv_timeout(PIPE_JOIN_TIMEOUT);
+        let buffer = self.buffer.lock().unwrap();
+        String::from_utf8_lossy(&buffer).into_owned()
+    }
+}
+
+/**
+ * パイプの内容を全て読み込むスレッドを起動する
+ * @param pipe 読み込むパイプ
+ * @return 読み込みスレッド
+ */
+fn spawn_pipe_reader<R: Read + Send + 'static>(mut pipe: R) -> PipeReader {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let (sender, finished) = mpsc::channel();
+    let thread_buffer = Arc::clone(&buffer);
+    thread::spawn(move || {
+        let mut chunk = [0; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(len) => thread_buffer.lock().unwrap().extend_from_slice(&chunk[..len]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        let _ = sender.send(());
+    });
+    PipeReader { buffer, finished }
+}
+
+/**
+ * 子プロセスの待機結果
+ */
+#[derive(Debug, Clone, Copy)]
+enum WaitStatus {
+    /* 終了した */
+    Exited(ExitStatus),
+    /* Time Limitを超えたためkillした */
+    TimeLimitExceeded,
+    /* 使用メモリ(RSS)がMemory Limitを超えたためkillした */
+    MemoryLimitExceeded,
+}
+
+/**
+ * 子プロセスの終了を待ち，Time LimitかMemory Limitを超えた場合はプロセスグループごとkillする
+ * (終了した場合もプロセスグループに残ったプロセスはkillする)
+ * (RSSで判定するため，cgroup/RLIMIT_DATAで制限できないJavaやAddressSanitizerも制限できる)
+ * @param child 待機する子プロセス
+ * @param start 実行開始時刻
+ * @param time_limit Time Limit
+ * @param memory_limit_kb Memory Limit(KB) (0の場合は制限しない)
+ * @return 異常終了: エラー
+ *         正常終了: (実行時間, 待機結果, 資源使用量)
+ */
+fn wait_with_timeout(
+    child: &mut Child,
+    start: Instant,
+    time_limit: Duration,
+    memory_limit_kb: u64,
+) -> Result<(Duration, WaitStatus, libc::rusage)> {
+    let pid = child.id() as libc::pid_t;
+    let mut raw_status = 0;
+    let mut usage: libc::rusage = unsafe { mem::zeroed() };
+    loop {
+        /* 使用メモリを取得するためtry_waitではなくwait4で回収する */
+        let waited = unsafe { libc::wait4(pid, &mut raw_status, libc::WNOHANG, &mut usage) };
+        if waited == pid {
+            let end = start.elapsed();
+            /* 終了後も残っている子プロセスが生成したプロセスをkillする (パイプを閉じさせる) */
+            unsafe {
+                libc::killpg(pid, libc::SIGKILL);
+            }
+            let exit_status = ExitStatus::from_raw(raw_status);
+            return Ok((end, WaitStatus::Exited(exit_status), usage));
+        }
+        if waited < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let wait_status = if start.elapsed() > time_limit {
+            Some(WaitStatus::TimeLimitExceeded)
+        } else if memory_limit_kb > 0 && resident_memory(pid) > memory_limit_kb {
+            Some(WaitStatus::MemoryLimitExceeded)
+        } else {
+            None
+        };
+        if let Some(wait_status) = wait_status {
+            /* 子プロセスが生成したプロセスも含めてkillする */
+            unsafe {
+                libc::killpg(pid, libc::SIGKILL);
+                libc::wait4(pid, &mut raw_status, 0, &mut usage);
+            }
+            return Ok((start.elapsed(), wait_status, usage));
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/**
+ * 判定結果の集計を出力
+ * @param summary 判定結果の集計
+ */
+fn print_summary(summary: &VerdictSummary) {
+    println!(
+        "{} {}: {}, {}: {}, {}: {}, {}: {}, {}: {} (testcase: {})",
+        PrintColorize::print_cyan(String::from("[ result ]")),
+        Verdict::AC.to_colored_string(),
+        summary.accepted,
+        Verdict::WA.to_colored_string(),
+        summary.wrong_answer,
+        Verdict::TLE.to_colored_string(),
+        summary.time_limit_exceeded,
+        Verdict::MLE.to_colored_string(),
+        summary.memory_limit_exceeded,
+        Verdict::RE.to_colored_string(),
+        summary.runtime_error,
+        summary.total()
+    );
+}
+
+/**
+ * 1テストケースの判定 (実行状態はsmartを優先し，どちらも正常終了した場合は出力を比較する)
+ * @param checker checkerのプログラム (存在しない場合はNone)
+ * @param input_path テストケースへのパス
+ * @param smart (smartの実行結果へのパス, smartの実行状態)
+ * @param stupid (stupidの実行結果へのパス, stupidの実行状態)
+ * @return 異常終了: エラー
+ *         正常終了: (判定結果, 原因がstupidの場合は" (stupid)", checkerのメッセージ)
+ */
+fn judge_testcase(
+    checker: Option<&Program>,
+    input_path: &Path,
+    smart: (&Path, Option<&ExecStatus>),
+    stupid: (&Path, Option<&ExecStatus>),
+) -> Result<(Verdict, &'static str, String)> {
+    let (smart_path, smart_status) = smart;
+    let (stupid_path, stupid_status) = stupid;
+    /* 実行状態が無いか，正常終了したのに実行結果が無い場合は実行結果なしとして扱う */
+    let resolve = |path: &Path, status: Option<&ExecStatus>| match status {
+        Some(ExecStatus::Done) if !path.is_file() => ExecStatus::NoOutput(String::new()),
+        Some(status) => status.clone(),
+        None => ExecStatus::NoOutput(String::new()),
+    };
+    let smart_status = resolve(smart_path, smart_status);
+    let stupid_status = resolve(stupid_path, stupid_status);
+    /* 実行状態の判定 (smartを優先する) */
+    let (verdict, culprit) = match (&smart_status, &stupid_status) {
+        (ExecStatus::NoOutput(_), _) => (Some(Verdict::RE), ""),
+        (ExecStatus::RE(_), _) => (Some(Verdict::RE), ""),
+        (ExecStatus::MLE, _) => (Some(Verdict::MLE), ""),
+        (ExecStatus::TLE, _) => (Some(Verdict::TLE), ""),
+        (_, ExecStatus::NoOutput(_)) => (Some(Verdict::RE), " (stupid)"),
+        (_, ExecStatus::RE(_)) => (Some(Verdict::RE), " (stupid)"),
+        (_, ExecStatus::MLE) => (Some(Verdict::MLE), " (stupid)"),
+        (_, ExecStatus::TLE) => (Some(Verdict::TLE), " (stupid)"),
+        _ => (None, ""),
+    };
+    /* 実行結果が無い場合はその旨を表示する */
+    let culprit_status = if culprit.is_empty() {
+        &smart_status
+    } else {
+        &stupid_status
+    };
+    if let ExecStatus::NoOutput(_) = culprit_status {
+        return Ok((Verdict::RE, culprit, String::from("no output")));
+    }
+    let (verdict, message) = match (verdict, checker) {
+        (Some(verdict), _) => (verdict, String::new()),
+        (None, Some(checker)) => exec_checker(checker, input_path, smart_path, stupid_path)?,
+        (None, None) => {
+            /* ファイルを読み込み */
+            let smart_content = MyFileIO::read_file(String::from(smart_path.to_str().unwrap()))?;
+            let stupid_content =
+                MyFileIO::read_file(String::from(stupid_path.to_str().unwrap()))?;
+            /* 比較 */
+            if Judge::compare(&smart_content, &stupid_content, &SETTING.judge) {
+                (Verdict::AC, String::new())
+            } else {
+                (Verdict::WA, String::new())
+            }
+        }
+    };
+    Ok((verdict, culprit, message))
+}
+
+/**
+ * WAのテストケースの入力と，smart/stupidの出力の差分を表示 (設定により保存)
+ * @param root_path テストディレクトリへの絶対パス
+ * @param testcase_name テストケース名
+ * @param smart_path smartの実行結果へのパス
+ * @param stupid_path stupidの実行結果へのパス
+ * @return 異常終了: エラー
+ *         正常終了: 正常終了の有無
+ */
+fn print_wrong_answer(
+    root_path: &Path,
+    testcase_name: &str,
+    smart_path: &Path,
+    stupid_path: &Path,
+) -> Result<()> {
+    if !SETTING.logging.show_diff && !SETTING.logging.save_diff {
+        return Ok(());
+    }
+    let smart_content = MyFileIO::read_file(String::from(smart_path.to_str().unwrap()))?;
+    let stupid_content = MyFileIO::read_file(String::from(stupid_path.to_str().unwrap()))?;
+    let diff = TextDiff::unified(&stupid_content, &smart_content, 3);
+
+    if SETTING.logging.show_diff {
+        /* 入力 */
+        let mut input_path = root_path.join("testcase").join(testcase_name);
+        input_path.set_extension("in");
+        println!("{}", PrintColorize::print_cyan(String::from("input:")));
+        print_truncated(&MyFileIO::read_file(String::from(input_path.to_str().unwrap()))?);
+
+        /* 最初に異なる位置 */
+        if let Some(difference) =
+            Judge::first_difference(&smart_content, &stupid_content, &SETTING.judge)
+        {
+            let quote = |part: Option<String>| match part {
+                Some(part) => format!("`{}`", part),
+                None => String::from("nothing"),
+            };
+            println!(
+                "first difference at line {}, column {}: expected {}, found {}",
+                difference.line,
+                difference.column,
+                quote(difference.expected),
+                quote(difference.found)
+            );
+        }
+
+        /* 差分 */
+        for line in diff.iter() {
+            println!("{}", TextDiff::colorize(line));
+        }
+    }
+    if SETTING.logging.save_diff {
+        let mut diff_path = root_path.join("cpstt_out/diff").join(testcase_name);
+        diff_path.set_extension("patch");
+        MyFileIO::write_file(&diff_path, &(diff.join("\n") + "\n"))?;
+    }
+    Ok(())
+}
+
+/**
+ * checkerで出力を判定する (testlib互換: checker <input> <output> <answer>)
+ * @param checker checkerのプログラム
+ * @param input_path テストケースへのパス
+ * @param output_path smartの実行結果へのパス
+ * @param answer_path stupidの実行結果へのパス
+ * @return 異常終了: エラー
+ *         正常終了: (判定結果, checkerのメッセージ)
+ */
+fn exec_checker(
+    checker: &Program,
+    input_path: &Path,
+    output_path: &Path,
+    answer_path: &Path,
+) -> Result<(Verdict, String)> {
+    let args = [input_path, output_path, answer_path];
+    let (status, message) = exec_testlib_program(checker, &args, Stdio::null(), input_path)?;
+
+    /* checker自体の異常はsmartのWAではなくエラーとする */
+    if status.code() == Some(CHECKER_FAIL_EXIT_CODE) {
+        PrintError::print_error(format!(
+            "checker failed on {}: {}",
+            input_path.file_name().unwrap().to_string_lossy(),
+            message
+        ));
+        bail!("Some Error is occurred!");
+    }
+
+    let verdict = if status.success() {
+        Verdict::AC
+    } else {
+        Verdict::WA
+    };
+    Ok((verdict, message))
+}
+
+/**
+ * checker/validatorを実行する (smart/stupidのMemory Limitは適用せず，Time Limitの数倍まで待つ)
+ * @param program 実行するプログラム
+ * @param args コマンドライン引数
+ * @param stdin 標準入力
+ * @param input_path 判定するテストケースへのパス (エラーの表示に使う)
+ * @return 異常終了: エラー (時間内に終了しない場合やシグナルで終了した場合)
+ *         正常終了: (終了状態, メッセージ)
+ */
+fn exec_testlib_program(
+    program: &Program,
+    args: &[&Path],
+    stdin: Stdio,
+    input_path: &Path,
+) -> Result<(ExitStatus, String)> {
+    let start = Instant::now();
+    let mut child = program
+        .run_command()
+        .args(args)
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| anyhow!("Failed to run {}: {}", program.name, e))?;
+    let stdout_handle = spawn_pipe_reader(child.stdout.take().unwrap());
+    let stderr_handle = spawn_pipe_reader(child.stderr.take().unwrap());
+    let time_limit =
+        Duration::from_millis(SETTING.execution.time_limit) * TESTLIB_TIME_LIMIT_FACTOR;
+    let (_, wait_status, _) = wait_with_timeout(&mut child, start, time_limit, 0)?;
+    let stderr = stderr_handle.join();
+    let stdout = stdout_handle.join();
+
+    let input_name = input_path.file_name().unwrap().to_string_lossy();
+    let failure = match wait_status {
+        WaitStatus::TimeLimitExceeded | WaitStatus::MemoryLimitExceeded => format!(
+            "{} exceeded {} ms on {}",
+            program.name,
+            time_limit.as_millis(),
+            input_name
+        ),
+        WaitStatus::Exited(status) => match status.signal() {
+            Some(signal) => format!(
+                "{} was killed by signal {} on {}",
+                program.name, signal, input_name
+            ),
+            None => return Ok((status, testlib_message(&stdout, &stderr))),
+        },
+    };
+    PrintError::print_error(failure);
+    bail!("Some Error is occurred!");
+}
+
+/**
+ * checker/validatorのメッセージを取り出す (testlibは標準エラー出力に書き込むため，空の場合のみ標準出力を使う)
+ * @param stdout 標準出力
+ * @param stderr 標準エラー出力
+ * @return メッセージ
+ */
+fn testlib_message(stdout: &str, stderr: &str) -> String {
+    if stderr.trim().is_empty() {
+        stdout.trim().to_string()
+    } else {
+        stderr.trim().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    /**
+     * 実行結果が無いテストケースの判定テスト
+     */
+    fn judge_testcase_no_output_test() {
+        let missing = Path::new("/nonexistent/cpstt_out/smart/0.diff");
+        let done = ExecStatus::Done;
+        assert_eq!(
+            judge_testcase(None, missing, (missing, Some(&done)), (missing, Some(&done))).unwrap(),
+            (Verdict::RE, "", String::from("no output"))
+        );
+        let tle = ExecStatus::TLE;
+        assert_eq!(
+            judge_testcase(None, missing, (missing, Some(&tle)), (missing, None)).unwrap(),
+            (Verdict::TLE, "", String::new())
+        );
+    }
+
+    #[test]
+    /**
+     * Time Limitを超えた子プロセスをプロセスグループごとkillするテスト
+     */
+    fn wait_with_timeout_test() {
+        /* 子プロセスがさらにsleepを起動し，そのpidを出力してから待機する */
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let start = Instant::now();
+        let mut stdout = io::BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        io::BufRead::read_line(&mut stdout, &mut line).unwrap();
+        let sleep_pid: u32 = line.trim().parse().unwrap();
+
+        let time_limit = Duration::from_millis(200);
+        let (time, wait_status, _) = wait_with_timeout(&mut child, start, time_limit, 0).unwrap();
+        assert!(matches!(wait_status, WaitStatus::TimeLimitExceeded));
+        assert!(time >= time_limit && time < time_limit + Duration::from_millis(500));
+
+        /* 孫プロセスもkillされている (回収されていないゾンビは終了済みとみなす) */
+        let state = fs::read_to_string(format!("/proc/{}/stat", sleep_pid))
+            .ok()
+            .and_then(|stat| stat.rsplit(") ").next().map(String::from));
+        if let Some(state) = state {
+            assert!(state.starts_with('Z'));
+        }
+    }
+
+    #[test]
+    /**
+     * プロセスグループ外に逃れた孫プロセスがパイプを保持していても読み込みを打ち切るテスト
+     */
+    fn pipe_reader_test() {
+        let mut child = Command::new("sh")
+            .args(["-c", "setsid sleep 30 & echo $!"])
+            .stdout(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let start = Instant::now();
+        let stdout_handle = spawn_pipe_reader(child.stdout.take().unwrap());
+        let time_limit = Duration::from_secs(10);
+        let (_, wait_status, _) = wait_with_timeout(&mut child, start, time_limit, 0).unwrap();
+        assert!(matches!(wait_status, WaitStatus::Exited(status) if status.success()));
+
+        let stdout = stdout_handle.join();
+        assert!(start.elapsed() < PIPE_JOIN_TIMEOUT + Duration::from_millis(500));
+        let sleep_pid: libc::pid_t = stdout.trim().parse().unwrap();
+        unsafe {
+            libc::kill(sleep_pid, libc::SIGKILL);
+        }
+    }
+
+    #[test]
+    /**
+     * cgroup/RLIMIT_DATAでMemory Limitを超えたプログラムを判定するテスト
+     */
+    fn memory_limit_test() {
+        let root_path =
+            std::env::temp_dir().join(format!("cpstt_memory_limit_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        MyFileIO::make_init_dir(root_path.clone()).unwrap();
+        fs::write(
+            root_path.join("smart.cpp"),
+            "#include <iostream>\n#include <vector>\nint main() { size_t n; std::cin >> n; \
+             std::vector<char> v(n << 20, 1); std::cout << v.back() + 0 << std::endl; }\n",
+        )
+        .unwrap();
+        let program = Program::find(&root_path, "smart").unwrap();
+        compile(&program).unwrap();
+        assert!(program.limits_data_segment());
+
+        /* 64MBに制限して，16MBと256MBを確保する */
+        let run = |megabytes: &str| {
+            let mut command = program.run_command();
+            command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped());
+            let (memory_limit, cgroup_procs) =
+                MemoryLimit::apply(&mut command, 64 * 1024, true).unwrap();
+            let mut child = command.spawn().unwrap();
+            drop(cgroup_procs);
+            io::Write::write_all(&mut child.stdin.take().unwrap(), megabytes.as_bytes()).unwrap();
+            let output = child.wait_with_output().unwrap();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            memory_limit.exceeded(!output.status.success(), &stderr)
+        };
+        assert!(!run("16"));
+        assert!(run("256"));
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * 実行状態の保存と読み込みテスト (cpstt exec → cpstt compare)
+     */
+    fn save_output_status_test() {
+        let root_path =
+            std::env::temp_dir().join(format!("cpstt_save_output_{}", std::process::id()));
+        fs::create_dir_all(root_path.join("cpstt_out/smart")).unwrap();
+        let testcase = root_path.join("testcase/0_sample_00.in");
+
+        let tle = ExecResult {
+            stdout: String::from("1\n"),
+            status: ExecStatus::TLE,
+            ..ExecResult::no_output(String::new())
+        };
+        let output_path = save_output(&root_path, "smart", &testcase, &tle).unwrap();
+        assert!(output_path.is_file());
+        assert_eq!(read_exec_report(&output_path).unwrap().exec_status(), ExecStatus::TLE);
+
+        /* 実行できなかった場合は前回の出力を残さない */
+        let no_output = ExecResult::no_output(String::from("not found"));
+        save_output(&root_path, "smart", &testcase, &no_output).unwrap();
+        assert!(!output_path.is_file());
+        let report = read_exec_report(&output_path).unwrap();
+        assert_eq!(report.exec_status(), no_output.status);
+        assert_eq!(report.output_path, None);
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * テストケースとsmart/stupidの実行結果の対応の確認テスト
+     */
+    fn check_outputs_test() {
+        let root_path =
+            std::env::temp_dir().join(format!("cpstt_check_outputs_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        MyFileIO::make_init_dir(root_path.clone()).unwrap();
+        let files = [
+            "testcase/0_sample_00.in",
+            "testcase/0_sample_01.in",
+            "testcase/0_sample_02.in",
+            "cpstt_out/smart/0_sample_00.diff",
+            "cpstt_out/smart/0_sample_01.diff",
+            "cpstt_out/smart/0_sample_03.diff",
+            "cpstt_out/stupid/0_sample_00.diff",
+            /* 実行できなかった場合は実行状態のみが保存される */
+            "cpstt_out/stupid/0_sample_01.status",
+            "cpstt_out/stupid/0_sample_01.err",
+        ];
+        for file in files {
+            fs::write(root_path.join(file), "").unwrap();
+        }
+        let testcases = list_testcases(&root_path, &[]).unwrap();
+        assert_eq!(
+            check_outputs(&root_path, &testcases).unwrap(),
+            vec![
+                "0_sample_02: no output from smart, stupid",
+                "0_sample_03: output of smart has no testcase",
+            ]
+        );
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * stressが停止する判定結果のテスト (test/のsmartは0_sample_02でWAとなる)
+     */
+    fn stress_test() {
+        let root_path = std::env::temp_dir().join(format!("cpstt_stress_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        let test_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test");
+        for file_name in ["generator.cpp", "smart.cpp", "stupid.cpp", "settings.toml"] {
+            fs::copy(test_path.join(file_name), root_path.join(file_name)).unwrap();
+        }
+        let run_options = RunOptions {
+            command: "stress",
+            rebuild: false,
+            jobs: 2,
+            fail_fast: false,
+            junit: None,
+            only: Vec::new(),
+        };
+        let stress_opts = |stop_on: Vec<Verdict>| StressOpts {
+            iterations: Some(2),
+            time_budget: None,
+            shrink: false,
+            stop_on,
+        };
+
+        /* WAで停止し，そのテストケースを返す */
+        let stop_on_wa = stress_opts(vec![Verdict::WA]);
+        let found = stress(root_path.clone(), Some(1), &stop_on_wa, &run_options);
+        assert_eq!(found.unwrap(), Some((1, String::from("0_sample_02"))));
+        /* 停止する判定結果が無ければ反復回数まで実行する */
+        let stop_on_tle = stress_opts(vec![Verdict::TLE]);
+        let found = stress(root_path.clone(), Some(1), &stop_on_tle, &run_options);
+        assert_eq!(found.unwrap(), None);
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * execで判定結果を使うオプションをエラーとするテスト
+     */
+    fn exec_options_test() {
+        let root_path = std::env::temp_dir().join(format!("cpstt_exec_{}", std::process::id()));
+        let run_options = |fail_fast: bool, junit: Option<PathBuf>| RunOptions {
+            command: "exec",
+            rebuild: false,
+            jobs: 1,
+            fail_fast,
+            junit,
+            only: Vec::new(),
+        };
+        assert!(exec(&root_path, None, &run_options(true, None)).is_err());
+        let junit = Some(root_path.join("junit.xml"));
+        assert!(exec(&root_path, None, &run_options(false, junit)).is_err());
+        /* 何も作成せずに終了する */
+        assert!(!root_path.exists());
+    }
+
+    #[test]
+    /**
+     * Javaの実行するクラス(Main)が無い場合にコンパイル時にエラーとするテスト
+     */
+    fn compile_java_main_class_test() {
+        /* javacが無い環境では確認しない */
+        if Command::new("javac").arg("-version").output().is_err() {
+            return;
+        }
+        let root_path = std::env::temp_dir().join(format!("cpstt_java_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        let source = "class CLASS { public static void main(String[] args) {} }\n";
+
+        fs::write(root_path.join("smart.java"), source.replace("CLASS", "Solution")).unwrap();
+        let program = Program::find(&root_path, "smart").unwrap();
+        assert!(compile(&program).is_err());
+        /* 以前のクラスファイルが残っていても正しく判定する */
+        fs::write(root_path.join("smart.java"), source.replace("CLASS", "Main")).unwrap();
+        assert!(compile(&program).is_ok());
+        fs::write(root_path.join("smart.java"), source.replace("CLASS", "Solution")).unwrap();
+        assert!(compile(&program).is_err());
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * validatorの判定テスト (シグナルで終了した場合は不正な入力ではなくエラーとする)
+     */
+    fn validate_test() {
+        let root_path =
+            std::env::temp_dir().join(format!("cpstt_validate_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        MyFileIO::make_init_dir(root_path.clone()).unwrap();
+        fs::write(
+            root_path.join("validator.cpp"),
+            "#include <cstdlib>\n#include <iostream>\nint main() { int n; std::cin >> n; \
+             if (n == 0) std::abort(); \
+             if (n > 10) { std::cerr << \"n out of range\"; return 3; } }\n",
+        )
+        .unwrap();
+        let validator = Program::find(&root_path, "validator").unwrap();
+        compile(&validator).unwrap();
+
+        let input_path = root_path.join("testcase/0_sample_00.in");
+        fs::write(&input_path, "5\n").unwrap();
+        assert_eq!(validate(&validator, &input_path).unwrap(), (true, String::new()));
+        fs::write(&input_path, "11\n").unwrap();
+        assert_eq!(
+            validate(&validator, &input_path).unwrap(),
+            (false, String::from("n out of range"))
+        );
+        fs::write(&input_path, "0\n").unwrap();
+        assert!(validate(&validator, &input_path).is_err());
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * 不正な入力に対してcheckerが異常終了しても最小化を続けるテスト
+     */
+    fn shrink_checker_failure_test() {
+        let root_path = std::env::temp_dir().join(format!("cpstt_shrink_{}", std::process::id()));
+        fs::create_dir_all(&root_path).unwrap();
+        MyFileIO::make_init_dir(root_path.clone()).unwrap();
+        /* smartは5より大きい値があると誤った和を出力する */
+        let sources = [
+            (
+                "smart.cpp",
+                "#include <iostream>\nint main() { long n, a, s = 0; bool w = false; \
+                 std::cin >> n; while (std::cin >> a) { s += a; w |= a > 5; } \
+                 std::cout << s + w << std::endl; }\n",
+            ),
+            (
+                "stupid.cpp",
+                "#include <iostream>\nint main() { long n, a, s = 0; std::cin >> n; \
+                 while (std::cin >> a) s += a; std::cout << s << std::endl; }\n",
+            ),
+            /* 1行目の個数と値の個数が一致しない入力ではtestlibの_failと同じく終了コード3で終了する */
+            (
+                "checker.cpp",
+                "#include <fstream>\nint main(int, char** argv) { std::ifstream in(argv[1]), \
+                 out(argv[2]), ans(argv[3]); long n, a, c = 0, x, y; in >> n; while (in >> a) c++; \
+                 if (c != n) return 3; out >> x; ans >> y; return x == y ? 0 : 1; }\n",
+            ),
+        ];
+        for (file_name, source) in sources {
+            fs::write(root_path.join(file_name), source).unwrap();
+        }
+        for name in ["smart", "stupid", "checker"] {
+            compile(&Program::find(&root_path, name).unwrap()).unwrap();
+        }
+        let testcase_path = root_path.join("0_random_00.in");
+        fs::write(&testcase_path, "3\n1 7 2\n").unwrap();
+
+        shrink(&root_path, &testcase_path).unwrap();
+        let minimized = fs::read_to_string(root_path.join("cpstt_out/shrink/0_random_00.in"));
+        assert_eq!(minimized.unwrap(), "3\n0 6 0\n");
+        fs::remove_dir_all(&root_path).unwrap();
+    }
+
+    #[test]
+    /**
+     * --onlyのパターンの一致判定テスト
+     */
+    fn glob_match_test() {
+        assert!(glob_match("0_sample_*", "0_sample_01"));
+        assert!(glob_match("*_0?", "1_random_03"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("0_sample_*", "1_random_00"));
+        assert!(!glob_match("?", ""));
+        assert!(!glob_match("a*c", "abcd"));
+    }
+
     #[test]
     /**
      * ファイル読み込みテスト
@@ -490,13 +2611,17 @@ mod tests {
      * generatorファイルの実行テスト
      */
     fn exec_generator_test() {
-        /* 正常ファイル */
-        let generator_path = MyFileIO::get_root_path().join("generator.cpp");
-        let mut generator_root_path = generator_path.clone();
-        generator_root_path.pop();
+        /* 正常ファイル (カレントディレクトリに依らないよう，--dirと同様にtestを指定する) */
+        MyFileIO::set_override(SettingsOverride {
+            dir: Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("test")),
+            ..SettingsOverride::default()
+        });
+        let generator_root_path = MyFileIO::get_root_path();
+        MyFileIO::make_init_dir(generator_root_path.clone()).unwrap();
+        let program = Program::find(&generator_root_path, "generator").unwrap();
+        compile(&program).unwrap();
         let args = vec![String::from(generator_root_path.to_str().unwrap())];
-        let exec_output =
-            exec_generator(generator_path.clone(), &args, &generator_root_path).unwrap();
+        let exec_output = exec_generator(&args, &generator_root_path).unwrap();
         assert_eq!(exec_output, String::from(""));
     }
 }
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/* RLIMIT_DATAで確保に失敗したプログラムが標準エラー出力に書き込むメッセージ (C++, C, Rust) */
+const ALLOCATION_FAILURE_MESSAGES: [&str; 3] = [
+    "std::bad_alloc",
+    "Cannot allocate memory",
+    "memory allocation of",
+];
+
+/* 実行ごとに作成するcgroupの通し番号 */
+static CGROUP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/**
+ * 実行するプログラムへのMemory Limitの適用方法
+ * (cgroup v2が使えればmemory.max，使えなければRLIMIT_DATAで制限する．
+ * いずれも使えないプログラムは実行中のRSSの監視のみで制限する)
+ */
+pub enum MemoryLimit {
+    /* 制限しない，またはRSSの監視のみで制限する */
+    Polling,
+    /* 実行ごとに作成したcgroupのmemory.maxで制限する */
+    Cgroup(PathBuf),
+    /* RLIMIT_DATAで制限する */
+    RlimitData,
+}
+
+impl MemoryLimit {
+    /**
+     * 起動前のコマンドにMemory Limitを適用する
+     * @param command 起動するコマンド
+     * @param memory_limit_kb Memory Limit(KB) (0の場合は制限しない)
+     * @param limits_data_segment RLIMIT_DATAで制限できるプログラムか
+     * @return 異常終了: エラー
+     *         正常終了: 適用方法 (cgroup.procsのファイルは起動するまで開いておく)
+     */
+    pub fn apply(
+        command: &mut Command,
+        memory_limit_kb: u64,
+        limits_data_segment: bool,
+    ) -> Result<(MemoryLimit, Option<File>)> {
+        if memory_limit_kb == 0 {
+            return Ok((MemoryLimit::Polling, None));
+        }
+        let memory_limit = memory_limit_kb
+            .checked_mul(1024)
+            .ok_or_else(|| anyhow!("memory_limit is too large"))?;
+
+        if let Some(cgroup_path) = MemoryLimit::create_cgroup(memory_limit) {
+            let procs = OpenOptions::new()
+                .write(true)
+                .open(cgroup_path.join("cgroup.procs"))?;
+            let procs_fd = procs.as_raw_fd();
+            /* execの前に子プロセス自身をcgroupに移す ("0"は書き込んだプロセス自身を表す) */
+            unsafe {
+                command.pre_exec(move || {
+                    if libc::write(procs_fd, b"0".as_ptr() as *const libc::c_void, 1) < 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+            return Ok((MemoryLimit::Cgroup(cgroup_path), Some(procs)));
+        }
+
+        if limits_data_segment {
+            let rlimit = libc::rlimit {
+                rlim_cur: memory_limit as libc::rlim_t,
+                rlim_max: memory_limit as libc::rlim_t,
+            };
+            unsafe {
+                command.pre_exec(move || {
+                    if libc::setrlimit(libc::RLIMIT_DATA, &rlimit) < 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+            return Ok((MemoryLimit::RlimitData, None));
+        }
+        Ok((MemoryLimit::Polling, None))
+    }
+
+    /**
+     * 終了したプログラムがMemory Limitを超えたかを判定する
+     * @param failed 異常終了したか
+     * @param stderr 標準エラー出力
+     * @return Memory Limitを超えていればtrue
+     */
+    pub fn exceeded(&self, failed: bool, stderr: &str) -> bool {
+        match self {
+            MemoryLimit::Polling => false,
+            /* memory.maxを超えるとOOM Killerにkillされる */
+            MemoryLimit::Cgroup(cgroup_path) => {
+                fs::read_to_string(cgroup_path.join("memory.events"))
+                    .ok()
+                    .and_then(|events| {
+                        events
+                            .lines()
+                            .find_map(|line| line.strip_prefix("oom_kill "))
+                            .and_then(|count| count.trim().parse::<u64>().ok())
+                    })
+                    .is_some_and(|count| count > 0)
+            }
+            /* RLIMIT_DATAを超えると確保に失敗するため，異常終了とそのメッセージで判定する */
+            MemoryLimit::RlimitData => {
+                failed
+                    && ALLOCATION_FAILURE_MESSAGES
+                        .iter()
+                        .any(|message| stderr.contains(message))
+            }
+        }
+    }
+
+    /**
+     * 自身のcgroupの下に，memory.maxを設定したcgroupを作成する
+     * (cgroup v2でmemoryコントローラが委譲されている場合のみ)
+     * @param memory_limit Memory Limit(バイト)
+     * @return 作成したcgroupへのパス (作成できない場合はNone)
+     */
+    fn create_cgroup(memory_limit: u64) -> Option<PathBuf> {
+        let parent = MemoryLimit::own_cgroup()?;
+        let controllers = fs::read_to_string(parent.join("cgroup.subtree_control")).ok()?;
+        if !controllers.split_whitespace().any(|name| name == "memory") {
+            return None;
+        }
+        let cgroup_path = parent.join(format!(
+            "cpstt_{}_{}",
+            std::process::id(),
+            CGROUP_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir(&cgroup_path).ok()?;
+        if fs::write(cgroup_path.join("memory.max"), memory_limit.to_string()).is_err() {
+            let _ = fs::remove_dir(&cgroup_path);
+            return None;
+        }
+        /* スワップせずにOOM Killerにkillされるようにする */
+        let _ = fs::write(cgroup_path.join("memory.swap.max"), "0");
+        Some(cgroup_path)
+    }
+
+    /**
+     * 自身が属するcgroup v2のディレクトリへのパスを取得
+     * @return cgroupへのパス (cgroup v2がマウントされていない場合はNone)
+     */
+    fn own_cgroup() -> Option<PathBuf> {
+        let cgroup = fs::read_to_string("/proc/self/cgroup").ok()?;
+        let relative = cgroup.lines().find_map(|line| line.strip_prefix("0::"))?;
+        /* mountinfo: <id> <parent> <major:minor> <root> <mount point> ... - <fstype> ... */
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+        let mount_point = mountinfo.lines().find_map(|line| {
+            let (mount, filesystem) = line.split_once(" - ")?;
+            if filesystem.split_whitespace().next()? != "cgroup2" {
+                return None;
+            }
+            mount.split_whitespace().nth(4).map(PathBuf::from)
+        })?;
+        Some(mount_point.join(relative.trim().trim_start_matches('/')))
+    }
+
+    /**
+     * cgroupを削除する (killしたプロセスが終了するまで少し待つ)
+     * @param cgroup_path cgroupへのパス
+     */
+    fn remove_cgroup(cgroup_path: &Path) {
+        for _ in 0..100 {
+            if fs::remove_dir(cgroup_path).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+impl Drop for MemoryLimit {
+    /**
+     * 作成したcgroupに残ったプロセスをkillしてcgroupを削除する
+     */
+    fn drop(&mut self) {
+        if let MemoryLimit::Cgroup(cgroup_path) = self {
+            let _ = fs::write(cgroup_path.join("cgroup.kill"), "1");
+            MemoryLimit::remove_cgroup(cgroup_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * RLIMIT_DATAでの確保の失敗をMemory Limit超過と判定するテスト
+     */
+    fn exceeded_test() {
+        let limit = MemoryLimit::RlimitData;
+        let stderr = "terminate called after throwing an instance of 'std::bad_alloc'";
+        assert!(limit.exceeded(true, stderr));
+        assert!(!limit.exceeded(false, stderr));
+        assert!(!limit.exceeded(true, "Segmentation fault"));
+        assert!(!MemoryLimit::Polling.exceeded(true, stderr));
+    }
+}
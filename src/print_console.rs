@@ -7,21 +7,18 @@ impl PrintError {
      * エラーを出力
      * @param msg エラー内容
      */
-    pub fn print_error(msg: String) -> Self {
+    pub fn print_error(msg: String) {
         eprint!("{}: ", Red.bold().paint("Error"));
         eprintln!("{}", msg);
-        Self {}
     }
 
     /**
      * WARNINGを出力
      * @param msg WARNING内容
      */
-    #[allow(dead_code)]
-    pub fn print_warning(msg: String) -> Self {
+    pub fn print_warning(msg: String) {
         eprint!("{}: ", Yellow.bold().paint("Warning"));
         eprintln!("{}", msg);
-        Self {}
     }
 }
 
@@ -33,7 +30,7 @@ impl PrintColorize {
      * @param msg 表示内容
      */
     pub fn print_green(msg: String) -> String {
-        return Green.bold().paint(msg).to_string();
+        Green.bold().paint(msg).to_string()
     }
 
     /**
@@ -41,7 +38,7 @@ impl PrintColorize {
      * @param msg 表示内容
      */
     pub fn print_yellow(msg: String) -> String {
-        return Yellow.bold().paint(msg).to_string();
+        Yellow.bold().paint(msg).to_string()
     }
 
     /**
@@ -49,6 +46,14 @@ impl PrintColorize {
      * @param msg 表示内容
      */
     pub fn print_cyan(msg: String) -> String {
-        return Cyan.bold().paint(msg).to_string();
+        Cyan.bold().paint(msg).to_string()
+    }
+
+    /**
+     * 色を赤色に変換
+     * @param msg 表示内容
+     */
+    pub fn print_red(msg: String) -> String {
+        Red.bold().paint(msg).to_string()
     }
 }
@@ -0,0 +1,382 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub use crate::fileio::{CompareMode, MyFileIO, TimeMeasure};
+pub use crate::verdict::{ExecResult, ExecStatus, Verdict, VerdictSummary};
+
+/* report.jsonの形式のバージョン (互換性の無い変更をしたら上げる) */
+pub const SCHEMA_VERSION: u32 = 1;
+/* HTML/JUnit XMLに1つのファイルから埋め込む最大文字数 */
+pub const MAX_EMBED_LEN: usize = 100_000;
+
+/**
+ * 1回のテストの結果 (cpstt_out/report.json)
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub schema_version: u32,
+    /* cpsttのバージョン */
+    pub cpstt_version: String,
+    /* 作成日時 (UNIX時間の秒) */
+    pub created_at: u64,
+    /* 作成したサブコマンド (run, stress or compare) */
+    #[serde(default)]
+    pub command: String,
+    /* テストディレクトリへの絶対パス */
+    pub root_path: String,
+    pub config: ConfigReport,
+    pub summary: SummaryReport,
+    pub testcases: Vec<TestcaseReport>,
+    /* --fail-fastで実行しなかったテストケース名 */
+    #[serde(default)]
+    pub skipped_testcases: Vec<String>,
+}
+
+/**
+ * テストの実行設定
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigReport {
+    /* generatorへ渡したシード値 */
+    pub seed: Option<u64>,
+    pub jobs: usize,
+    pub fail_fast: bool,
+    pub time_limit_ms: u64,
+    /* 0の場合は制限なし */
+    pub memory_limit_mb: u64,
+    pub time_measure: TimeMeasure,
+    pub compare: CompareMode,
+    pub abs_eps: f64,
+    pub rel_eps: f64,
+    /* 各プログラムのソースファイルへのパス (存在しない場合はNone) */
+    pub generator: Option<String>,
+    pub smart: Option<String>,
+    pub stupid: Option<String>,
+    pub checker: Option<String>,
+    pub validator: Option<String>,
+}
+
+/**
+ * 判定結果の集計
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryReport {
+    pub total: usize,
+    pub accepted: usize,
+    pub wrong_answer: usize,
+    pub time_limit_exceeded: usize,
+    pub memory_limit_exceeded: usize,
+    pub runtime_error: usize,
+    /* --fail-fastで実行しなかったテストケース数 */
+    pub skipped: usize,
+}
+
+/**
+ * 1テストケースの判定結果
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestcaseReport {
+    pub name: String,
+    /* AC, WA, TLE, MLE or RE */
+    pub verdict: String,
+    /* TLE/MLE/REの原因となったプログラム (smart or stupid) */
+    pub culprit: Option<String>,
+    /* checkerのメッセージなど */
+    pub message: String,
+    pub input_path: String,
+    pub smart: ExecReport,
+    pub stupid: ExecReport,
+}
+
+/**
+ * smart/stupidの1回の実行結果
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecReport {
+    /* done, TLE, MLE, RE or no output */
+    pub status: String,
+    /* REの詳細や実行できなかった理由 */
+    pub detail: Option<String>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub time_ms: u64,
+    pub cpu_time_ms: u64,
+    pub memory_kb: u64,
+    /* 標準出力・標準エラー出力の保存先 (保存していない場合はNone) */
+    pub output_path: Option<String>,
+    pub stderr_path: Option<String>,
+}
+
+impl Report {
+    /**
+     * report.jsonに書き込む
+     * @param path 保存先
+     * @return 正常終了の有無
+     */
+    pub fn write(&self, path: &Path) -> Result<()> {
+        MyFileIO::write_file(
+            &path.to_path_buf(),
+            &(serde_json::to_string_pretty(self)? + "\n"),
+        )
+    }
+
+    /**
+     * report.jsonを読み込む
+     * @param path report.jsonへのパス
+     * @return 異常終了: エラー
+     *         正常終了: テストの結果
+     */
+    pub fn read(path: &Path) -> Result<Report> {
+        let content = MyFileIO::read_file(String::from(path.to_str().unwrap()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /**
+     * HTML/JUnit XMLに埋め込むため，保存されたファイルを読み込む (存在しない場合は空文字列)
+     * @param path ファイルへのパス
+     * @return ファイルの内容 (MAX_EMBED_LEN文字まで)
+     */
+    pub fn read_embedded(path: &Option<String>) -> String {
+        let content = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        Report::truncate(content)
+    }
+
+    /**
+     * 埋め込む文字列が長すぎる場合は切り詰める
+     * @param content 文字列
+     * @return MAX_EMBED_LEN文字までの文字列
+     */
+    pub fn truncate(content: String) -> String {
+        if content.chars().count() <= MAX_EMBED_LEN {
+            return content;
+        }
+        let mut truncated: String = content.chars().take(MAX_EMBED_LEN).collect();
+        truncated += "\n... (truncated)";
+        truncated
+    }
+}
+
+impl SummaryReport {
+    /**
+     * 判定結果の集計から作成
+     * @param summary 判定結果の集計
+     * @param skipped 実行しなかったテストケース数
+     * @return 判定結果の集計
+     */
+    pub fn new(summary: &VerdictSummary, skipped: usize) -> Self {
+        SummaryReport {
+            total: summary.total(),
+            accepted: summary.accepted,
+            wrong_answer: summary.wrong_answer,
+            time_limit_exceeded: summary.time_limit_exceeded,
+            memory_limit_exceeded: summary.memory_limit_exceeded,
+            runtime_error: summary.runtime_error,
+            skipped,
+        }
+    }
+}
+
+impl TestcaseReport {
+    /**
+     * 判定結果から作成
+     * @param name テストケース名
+     * @param input_path テストケースへのパス
+     * @param verdict 判定結果
+     * @param culprit_is_stupid 原因がstupidか
+     * @param message checkerのメッセージなど
+     * @param smart smartの実行結果
+     * @param stupid stupidの実行結果
+     * @return 1テストケースの判定結果
+     */
+    pub fn new(
+        name: &str,
+        input_path: &Path,
+        verdict: Verdict,
+        culprit_is_stupid: bool,
+        message: &str,
+        smart: ExecReport,
+        stupid: ExecReport,
+    ) -> Self {
+        let culprit = match verdict {
+            Verdict::AC | Verdict::WA => None,
+            _ if culprit_is_stupid => Some(String::from("stupid")),
+            _ => Some(String::from("smart")),
+        };
+        TestcaseReport {
+            name: String::from(name),
+            verdict: verdict.to_string(),
+            culprit,
+            message: String::from(message),
+            input_path: input_path.to_string_lossy().into_owned(),
+            smart,
+            stupid,
+        }
+    }
+}
+
+impl ExecReport {
+    /**
+     * 実行結果から作成
+     * @param result 実行結果
+     * @param output_path 標準出力の保存先
+     * @return 実行結果
+     */
+    pub fn new(result: &ExecResult, output_path: &Path) -> Self {
+        let (status, detail) = match &result.status {
+            ExecStatus::Done => ("done", None),
+            ExecStatus::TLE => ("TLE", None),
+            ExecStatus::MLE => ("MLE", None),
+            ExecStatus::RE(detail) => ("RE", Some(detail.clone())),
+            ExecStatus::NoOutput(reason) => ("no output", Some(reason.clone())),
+        };
+        let stderr_path = output_path.with_extension("err");
+        ExecReport {
+            status: String::from(status),
+            detail,
+            exit_code: result.exit_code,
+            signal: result.signal,
+            time_ms: result.time.as_millis() as u64,
+            cpu_time_ms: result.cpu_time.as_millis() as u64,
+            memory_kb: result.memory,
+            output_path: Some(output_path)
+                .filter(|path| path.is_file())
+                .map(|path| path.to_string_lossy().into_owned()),
+            stderr_path: Some(stderr_path.as_path())
+                .filter(|path| !result.stderr.is_empty() && path.is_file())
+                .map(|path| path.to_string_lossy().into_owned()),
+        }
+    }
+
+    /**
+     * 実行状態に戻す
+     * @return 実行状態
+     */
+    pub fn exec_status(&self) -> ExecStatus {
+        let detail = self.detail.clone().unwrap_or_default();
+        match self.status.as_str() {
+            "TLE" => ExecStatus::TLE,
+            "MLE" => ExecStatus::MLE,
+            "RE" => ExecStatus::RE(detail),
+            "no output" => ExecStatus::NoOutput(detail),
+            _ => ExecStatus::Done,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /* SCHEMA_VERSION = 1 のreport.jsonの例 (形式を変えたらSCHEMA_VERSIONと共に更新する) */
+    const REPORT_V1: &str = r#"{
+  "schema_version": 1,
+  "cpstt_version": "1.1.1",
+  "created_at": 1700000000,
+  "command": "run",
+  "root_path": "/work/abc000_a",
+  "config": {
+    "seed": 42,
+    "jobs": 4,
+    "fail_fast": true,
+    "time_limit_ms": 2000,
+    "memory_limit_mb": 1024,
+    "time_measure": "wall",
+    "compare": "exact",
+    "abs_eps": 0.0,
+    "rel_eps": 0.0,
+    "generator": "/work/abc000_a/generator.cpp",
+    "smart": "/work/abc000_a/smart.cpp",
+    "stupid": "/work/abc000_a/stupid.cpp",
+    "checker": null,
+    "validator": null
+  },
+  "summary": {
+    "total": 1,
+    "accepted": 0,
+    "wrong_answer": 0,
+    "time_limit_exceeded": 0,
+    "memory_limit_exceeded": 0,
+    "runtime_error": 1,
+    "skipped": 1
+  },
+  "testcases": [
+    {
+      "name": "0_sample_00",
+      "verdict": "RE",
+      "culprit": "smart",
+      "message": "",
+      "input_path": "/work/abc000_a/testcase/0_sample_00.in",
+      "smart": {
+        "status": "RE",
+        "detail": "signal 11",
+        "exit_code": null,
+        "signal": 11,
+        "time_ms": 12,
+        "cpu_time_ms": 10,
+        "memory_kb": 3400,
+        "output_path": "/work/abc000_a/cpstt_out/smart/0_sample_00.diff",
+        "stderr_path": null
+      },
+      "stupid": {
+        "status": "done",
+        "detail": null,
+        "exit_code": 0,
+        "signal": null,
+        "time_ms": 30,
+        "cpu_time_ms": 28,
+        "memory_kb": 3500,
+        "output_path": "/work/abc000_a/cpstt_out/stupid/0_sample_00.diff",
+        "stderr_path": null
+      }
+    }
+  ],
+  "skipped_testcases": [
+    "0_sample_01"
+  ]
+}"#;
+
+    #[test]
+    /**
+     * report.jsonの読み込みと書き込みで内容が変わらないかのテスト
+     */
+    fn round_trip_test() {
+        assert_eq!(SCHEMA_VERSION, 1);
+        let report: Report = serde_json::from_str(REPORT_V1).unwrap();
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(
+            report.testcases[0].smart.exec_status(),
+            ExecStatus::RE(String::from("signal 11"))
+        );
+        assert_eq!(serde_json::to_string_pretty(&report).unwrap(), REPORT_V1);
+    }
+
+    #[test]
+    /**
+     * 実行結果から作成した実行状態を戻すテスト (cpstt exec → cpstt compare)
+     */
+    fn exec_status_test() {
+        let statuses = [
+            ExecStatus::Done,
+            ExecStatus::TLE,
+            ExecStatus::MLE,
+            ExecStatus::RE(String::from("exit code 1")),
+            ExecStatus::NoOutput(String::from("not found")),
+        ];
+        for status in statuses {
+            let result = ExecResult {
+                status: status.clone(),
+                time: Duration::from_millis(5),
+                ..ExecResult::no_output(String::new())
+            };
+            let report = ExecReport::new(&result, Path::new("/nonexistent/0_sample_00.diff"));
+            assert_eq!(report.exec_status(), status);
+        }
+    }
+}
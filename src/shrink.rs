@@ -0,0 +1,200 @@
+use anyhow::Result;
+
+pub struct Shrinker {}
+
+impl Shrinker {
+    /**
+     * 失敗し続ける範囲で入力を小さくする (行の削除→トークンの削除→数値の縮小 を変化しなくなるまで繰り返す)
+     * @param input 元の入力
+     * @param is_failing 候補の入力がまだ失敗するかを判定する関数
+     * @return 異常終了: エラー
+     *         正常終了: 最小化した入力
+     */
+    pub fn minimize(
+        input: &str,
+        is_failing: &mut dyn FnMut(&str) -> Result<bool>,
+    ) -> Result<String> {
+        let mut current = String::from(input);
+        loop {
+            let before = current.clone();
+            current = Shrinker::remove_lines(&current, is_failing)?;
+            current = Shrinker::remove_tokens(&current, is_failing)?;
+            current = Shrinker::shrink_numbers(&current, is_failing)?;
+            if current == before {
+                return Ok(current);
+            }
+        }
+    }
+
+    /**
+     * 行をまとめて削除する
+     * @param input 入力
+     * @param is_failing 候補の入力がまだ失敗するかを判定する関数
+     * @return 異常終了: エラー
+     *         正常終了: 行を削除した入力
+     */
+    fn remove_lines(
+        input: &str,
+        is_failing: &mut dyn FnMut(&str) -> Result<bool>,
+    ) -> Result<String> {
+        let lines: Vec<String> = input.lines().map(String::from).collect();
+        let lines = Shrinker::remove_chunks(lines, &Shrinker::join_lines, is_failing)?;
+        Ok(Shrinker::join_lines(&lines))
+    }
+
+    /**
+     * 行ごとに空白区切りのトークンをまとめて削除する
+     * @param input 入力
+     * @param is_failing 候補の入力がまだ失敗するかを判定する関数
+     * @return 異常終了: エラー
+     *         正常終了: トークンを削除した入力
+     */
+    fn remove_tokens(
+        input: &str,
+        is_failing: &mut dyn FnMut(&str) -> Result<bool>,
+    ) -> Result<String> {
+        let mut lines: Vec<Vec<String>> = input
+            .lines()
+            .map(|line| line.split_whitespace().map(String::from).collect())
+            .collect();
+        for i in 0..lines.len() {
+            if lines[i].len() <= 1 {
+                continue;
+            }
+            let tokens = lines[i].clone();
+            let (before, after) = (lines[..i].to_vec(), lines[i + 1..].to_vec());
+            let build = |tokens: &[String]| -> String {
+                let mut candidate = before.clone();
+                candidate.push(tokens.to_vec());
+                candidate.extend(after.iter().cloned());
+                Shrinker::join_tokens(&candidate)
+            };
+            lines[i] = Shrinker::remove_chunks(tokens, &build, is_failing)?;
+        }
+        Ok(Shrinker::join_tokens(&lines))
+    }
+
+    /**
+     * 整数のトークンを0に近づける (失敗する最小の絶対値を二分探索する)
+     * @param input 入力
+     * @param is_failing 候補の入力がまだ失敗するかを判定する関数
+     * @return 異常終了: エラー
+     *         正常終了: 数値を縮小した入力
+     */
+    fn shrink_numbers(
+        input: &str,
+        is_failing: &mut dyn FnMut(&str) -> Result<bool>,
+    ) -> Result<String> {
+        let mut lines: Vec<Vec<String>> = input
+            .lines()
+            .map(|line| line.split_whitespace().map(String::from).collect())
+            .collect();
+        for i in 0..lines.len() {
+            for j in 0..lines[i].len() {
+                let value = match lines[i][j].parse::<i64>() {
+                    Ok(value) if value != 0 => value,
+                    _ => continue,
+                };
+                /* 絶対値が lo 未満では失敗しないとみなし，hi では失敗することが分かっている */
+                let (mut lo, mut hi) = (0, value.unsigned_abs());
+                while lo < hi {
+                    let mid = lo + (hi - lo) / 2;
+                    let token = if value < 0 && mid != 0 {
+                        format!("-{}", mid)
+                    } else {
+                        mid.to_string()
+                    };
+                    let mut candidate = lines.clone();
+                    candidate[i][j] = token;
+                    if is_failing(&Shrinker::join_tokens(&candidate))? {
+                        lines = candidate;
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+            }
+        }
+        Ok(Shrinker::join_tokens(&lines))
+    }
+
+    /**
+     * 要素を大きな塊から順に削除し，失敗し続ける限り削除を採用する
+     * @param units 削除の単位となる要素
+     * @param build 要素から入力を組み立てる関数
+     * @param is_failing 候補の入力がまだ失敗するかを判定する関数
+     * @return 異常終了: エラー
+     *         正常終了: 残った要素
+     */
+    fn remove_chunks(
+        mut units: Vec<String>,
+        build: &dyn Fn(&[String]) -> String,
+        is_failing: &mut dyn FnMut(&str) -> Result<bool>,
+    ) -> Result<Vec<String>> {
+        let mut chunk = (units.len() / 2).max(1);
+        loop {
+            let mut i = 0;
+            while i < units.len() {
+                let end = (i + chunk).min(units.len());
+                let candidate: Vec<String> = units[..i]
+                    .iter()
+                    .chain(units[end..].iter())
+                    .cloned()
+                    .collect();
+                if is_failing(&build(&candidate))? {
+                    units = candidate;
+                } else {
+                    i += chunk;
+                }
+            }
+            if chunk == 1 {
+                return Ok(units);
+            }
+            chunk /= 2;
+        }
+    }
+
+    /**
+     * 行を連結して入力を組み立てる
+     * @param lines 行の一覧
+     * @return 入力
+     */
+    fn join_lines(lines: &[String]) -> String {
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    /**
+     * 行ごとのトークンを空白区切りで連結して入力を組み立てる
+     * @param lines 行ごとのトークンの一覧
+     * @return 入力
+     */
+    fn join_tokens(lines: &[Vec<String>]) -> String {
+        lines
+            .iter()
+            .map(|tokens| format!("{}\n", tokens.join(" ")))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * 入力の最小化テスト (5以上の数値が含まれていれば失敗とする)
+     */
+    fn minimize_test() {
+        let input = "3\n1 2 30\n4 5\n-7\n";
+        let mut runs = 0;
+        let minimized = Shrinker::minimize(input, &mut |candidate| {
+            runs += 1;
+            Ok(candidate
+                .split_whitespace()
+                .any(|token| token.parse::<i64>().is_ok_and(|value| value >= 5)))
+        })
+        .unwrap();
+        assert_eq!(minimized, "5\n");
+        assert!(runs > 0);
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::{bail, Result};
+
+pub use crate::print_console::PrintError;
+
+/* settings.tomlのテンプレート (READMEとtest/の設定例と共通) */
+const SETTINGS: &str = include_str!("../test/settings.toml");
+
+/* 1つの入力に1つのケース */
+const SINGLE_GENERATOR: &str = include_str!("../templates/single/generator.cpp");
+const SINGLE_SOLUTION: &str = include_str!("../templates/single/solution.cpp");
+/* 1つの入力に複数のケース (1行目がケース数) */
+const MULTI_GENERATOR: &str = include_str!("../templates/multi/generator.cpp");
+const MULTI_SOLUTION: &str = include_str!("../templates/multi/solution.cpp");
+/* グラフ・木の入力 (1行目が頂点数と辺数) */
+const GRAPH_GENERATOR: &str = include_str!("../templates/graph/generator.cpp");
+const GRAPH_SOLUTION: &str = include_str!("../templates/graph/solution.cpp");
+
+pub struct Templates {}
+
+impl Templates {
+    /* テンプレート名の一覧 */
+    pub const NAMES: [&'static str; 3] = ["single", "multi", "graph"];
+
+    /**
+     * テンプレートのファイルの一覧を取得
+     * @param name テンプレート名
+     * @return 異常終了: エラー
+     *         正常終了: (ファイル名, 内容) の一覧
+     */
+    pub fn files(name: &str) -> Result<Vec<(&'static str, &'static str)>> {
+        let (generator, solution) = match name {
+            "single" => (SINGLE_GENERATOR, SINGLE_SOLUTION),
+            "multi" => (MULTI_GENERATOR, MULTI_SOLUTION),
+            "graph" => (GRAPH_GENERATOR, GRAPH_SOLUTION),
+            _ => {
+                PrintError::print_error(format!(
+                    "Unknown template {} (available: {})",
+                    name,
+                    Templates::NAMES.join(", ")
+                ));
+                bail!("Some Error is occurred!");
+            }
+        };
+        /* smartとstupidは同じ雛形から書き始める */
+        Ok(vec![
+            ("generator.cpp", generator),
+            ("smart.cpp", solution),
+            ("stupid.cpp", solution),
+            ("settings.toml", SETTINGS),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /**
+     * テンプレートのファイルの一覧の取得テスト
+     */
+    fn files_test() {
+        for name in Templates::NAMES {
+            let files = Templates::files(name).unwrap();
+            let file_names: Vec<&str> = files.iter().map(|(file_name, _)| *file_name).collect();
+            assert_eq!(
+                file_names,
+                vec!["generator.cpp", "smart.cpp", "stupid.cpp", "settings.toml"]
+            );
+            /* generatorはinit/outputの雛形を含む */
+            assert!(files[0].1.contains("void init(int argc, char *argv[])"));
+            assert!(files[0].1.contains("void output("));
+        }
+        assert!(Templates::files("unknown").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub use crate::print_console::PrintColorize;
+
+/**
+ * smart/stupidの1テストケースの実行状態
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExecStatus {
+    /* 正常終了 */
+    Done,
+    /* Time Limit超過 */
+    TLE,
+    /* Memory Limit超過 */
+    MLE,
+    /* 実行時エラー (終了コードまたはシグナルの説明) */
+    RE(String),
+    /* 実行できず，実行結果が無い (理由) */
+    NoOutput(String),
+}
+
+impl ExecStatus {
+    /**
+     * 子プロセスの終了状態から実行状態を判定
+     * @param status 子プロセスの終了状態
+     * @return 実行状態
+     */
+    pub fn from_exit_status(status: ExitStatus) -> Self {
+        if status.success() {
+            return ExecStatus::Done;
+        }
+        match (status.code(), status.signal()) {
+            (Some(code), _) => ExecStatus::RE(format!("exit code {}", code)),
+            (None, Some(signal)) => ExecStatus::RE(format!(
+                "signal {}: {}",
+                signal,
+                ExecStatus::signal_name(signal)
+            )),
+            (None, None) => ExecStatus::RE(String::from("unknown status")),
+        }
+    }
+
+    /**
+     * シグナル番号からシグナル名を取得
+     * @param signal シグナル番号
+     * @return シグナル名
+     */
+    fn signal_name(signal: i32) -> &'static str {
+        match signal {
+            libc::SIGSEGV => "SIGSEGV",
+            libc::SIGFPE => "SIGFPE",
+            libc::SIGABRT => "SIGABRT",
+            libc::SIGBUS => "SIGBUS",
+            libc::SIGILL => "SIGILL",
+            libc::SIGKILL => "SIGKILL",
+            libc::SIGTERM => "SIGTERM",
+            _ => "unknown signal",
+        }
+    }
+
+    /**
+     * 1行表示用の色付き文字列
+     * @return 表示内容
+     */
+    pub fn to_colored_string(&self) -> String {
+        match self {
+            ExecStatus::Done => String::from("done"),
+            ExecStatus::TLE => PrintColorize::print_yellow(String::from("TLE")),
+            ExecStatus::MLE => PrintColorize::print_yellow(String::from("MLE")),
+            ExecStatus::RE(detail) => format!(
+                "{} ({})",
+                PrintColorize::print_yellow(String::from("RE")),
+                detail
+            ),
+            ExecStatus::NoOutput(reason) => format!(
+                "{} ({})",
+                PrintColorize::print_yellow(String::from("no output")),
+                reason
+            ),
+        }
+    }
+}
+
+/**
+ * プログラムを1回実行した結果
+ */
+#[derive(Debug, Clone)]
+pub struct ExecResult {
+    /* 標準出力 */
+    pub stdout: String,
+    /* 標準エラー出力 */
+    pub stderr: String,
+    /* 終了コード (シグナルで終了した場合やTLEの場合はNone) */
+    pub exit_code: Option<i32>,
+    /* 終了させたシグナル */
+    pub signal: Option<i32>,
+    /* 実行時間 */
+    pub time: Duration,
+    /* CPU時間 (user + sys) */
+    pub cpu_time: Duration,
+    /* 最大使用メモリ(KB) */
+    pub memory: u64,
+    /* 実行状態 */
+    pub status: ExecStatus,
+}
+
+impl ExecResult {
+    /**
+     * 実行できなかった場合の実行結果
+     * @param reason 実行できなかった理由
+     * @return 実行結果
+     */
+    pub fn no_output(reason: String) -> Self {
+        ExecResult {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: None,
+            signal: None,
+            time: Duration::from_secs(0),
+            cpu_time: Duration::from_secs(0),
+            memory: 0,
+            status: ExecStatus::NoOutput(reason),
+        }
+    }
+}
+
+/**
+ * smartとstupidを比較した1テストケースの判定結果
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    AC,
+    WA,
+    TLE,
+    MLE,
+    RE,
+}
+
+impl Verdict {
+    /**
+     * 1行表示用の色付き文字列
+     * @return 表示内容
+     */
+    pub fn to_colored_string(self) -> String {
+        match self {
+            Verdict::AC => PrintColorize::print_green(self.to_string()),
+            _ => PrintColorize::print_yellow(self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Verdict::AC => "AC",
+            Verdict::WA => "WA",
+            Verdict::TLE => "TLE",
+            Verdict::MLE => "MLE",
+            Verdict::RE => "RE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AC" => Ok(Verdict::AC),
+            "WA" => Ok(Verdict::WA),
+            "TLE" => Ok(Verdict::TLE),
+            "MLE" => Ok(Verdict::MLE),
+            "RE" => Ok(Verdict::RE),
+            _ => Err(anyhow!("unknown verdict: {}", s)),
+        }
+    }
+}
+
+/**
+ * 判定結果の集計
+ */
+#[derive(Debug, Default, Clone)]
+pub struct VerdictSummary {
+    pub accepted: usize,
+    pub wrong_answer: usize,
+    pub time_limit_exceeded: usize,
+    pub memory_limit_exceeded: usize,
+    pub runtime_error: usize,
+    /* AC以外の (テストケース名, 判定結果) */
+    pub failed: Vec<(String, Verdict)>,
+}
+
+impl VerdictSummary {
+    /**
+     * 判定結果を1件追加
+     * @param testcase_name テストケース名
+     * @param verdict 判定結果
+     */
+    pub fn add(&mut self, testcase_name: &str, verdict: Verdict) {
+        if verdict != Verdict::AC {
+            self.failed.push((String::from(testcase_name), verdict));
+        }
+        match verdict {
+            Verdict::AC => self.accepted += 1,
+            Verdict::WA => self.wrong_answer += 1,
+            Verdict::TLE => self.time_limit_exceeded += 1,
+            Verdict::MLE => self.memory_limit_exceeded += 1,
+            Verdict::RE => self.runtime_error += 1,
+        }
+    }
+
+    /**
+     * 集計したテストケース数
+     * @return テストケース数
+     */
+    pub fn total(&self) -> usize {
+        self.accepted
+            + self.wrong_answer
+            + self.time_limit_exceeded
+            + self.memory_limit_exceeded
+            + self.runtime_error
+    }
+
+    /**
+     * 全てのテストケースがACかどうか
+     * @return 全てACならtrue
+     */
+    pub fn is_all_accepted(&self) -> bool {
+        self.accepted == self.total()
+    }
+
+    /**
+     * 指定した判定結果となった最初のテストケース
+     * @param verdicts 探す判定結果
+     * @return (テストケース名, 判定結果) (無い場合はNone)
+     */
+    pub fn first_failure(&self, verdicts: &[Verdict]) -> Option<(&str, Verdict)> {
+        self.failed
+            .iter()
+            .find(|(_, verdict)| verdicts.contains(verdict))
+            .map(|(testcase_name, verdict)| (testcase_name.as_str(), *verdict))
+    }
+}
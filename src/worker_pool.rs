@@ -0,0 +1,75 @@
+use std::sync::Mutex;
+use std::thread;
+
+pub struct WorkerPool {}
+
+impl WorkerPool {
+    /**
+     * 並列実行数の既定値 (CPUのコア数)
+     * @return 並列実行数
+     */
+    pub fn default_jobs() -> usize {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    }
+
+    /**
+     * 最大jobs個のスレッドで全ての要素に関数を適用する
+     * @param jobs 並列実行数
+     * @param items 処理する要素
+     * @param f 各要素に適用する関数
+     * @return 要素と同じ順番の結果
+     */
+    pub fn map<T, R, F>(jobs: usize, items: Vec<T>, f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(T) -> R + Sync,
+    {
+        let workers = jobs.max(1).min(items.len());
+        let queue = Mutex::new(items.into_iter().enumerate());
+        let results = Mutex::new(Vec::new());
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    /* キューから次の要素を取り出す (ロックは関数の実行前に解放する) */
+                    let next = queue.lock().unwrap().next();
+                    match next {
+                        Some((index, item)) => {
+                            let result = f(item);
+                            results.lock().unwrap().push((index, result));
+                        }
+                        None => break,
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    /**
+     * 並列実行数の上限と結果の順番のテスト
+     */
+    fn map_test() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let results = WorkerPool::map(3, (0..20).collect(), |x: usize| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(std::time::Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+            x * 2
+        });
+        assert_eq!(results, (0..20).map(|x| x * 2).collect::<Vec<usize>>());
+        assert!(max_running.load(Ordering::SeqCst) <= 3);
+    }
+}
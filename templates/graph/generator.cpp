@@ -0,0 +1,87 @@
+#include <algorithm>
+#include <fstream>
+#include <iostream>
+#include <numeric>
+#include <random>
+#include <set>
+#include <string>
+#include <utility>
+#include <vector>
+using namespace std;
+
+string root_path = "";
+mt19937_64 rng;
+
+// argv[1]: テストディレクトリへのパス
+// argv[2]: シード値 (cpstt stress 実行時のみ)
+void init(int argc, char *argv[]) {
+    root_path = std::string(argv[1]);
+    if (argc > 2) {
+        rng.seed((unsigned long long)(atoll(argv[2])));
+    } else {
+        rng.seed(random_device()());
+    }
+}
+
+// [lo, hi] の一様乱数
+long long rand_int(long long lo, long long hi) {
+    return uniform_int_distribution<long long>(lo, hi)(rng);
+}
+
+// 頂点数nのランダムな木の辺 (頂点番号は1始まりで，番号をシャッフルする)
+vector<pair<int, int>> random_tree(int n) {
+    vector<int> label(n);
+    iota(label.begin(), label.end(), 1);
+    shuffle(label.begin(), label.end(), rng);
+    vector<pair<int, int>> edges;
+    for (int v = 1; v < n; v++) {
+        edges.emplace_back(label[rand_int(0, v - 1)], label[v]);
+    }
+    shuffle(edges.begin(), edges.end(), rng);
+    return edges;
+}
+
+// 頂点数n・辺数mのランダムな連結単純グラフの辺 (n - 1 <= m <= n(n-1)/2)
+vector<pair<int, int>> random_connected_graph(int n, int m) {
+    vector<pair<int, int>> edges = random_tree(n);
+    set<pair<int, int>> used;
+    for (auto [u, v] : edges) used.emplace(min(u, v), max(u, v));
+    while ((int)edges.size() < m) {
+        int u = rand_int(1, n), v = rand_int(1, n);
+        if (u == v || used.count({min(u, v), max(u, v)})) continue;
+        used.emplace(min(u, v), max(u, v));
+        edges.emplace_back(u, v);
+    }
+    shuffle(edges.begin(), edges.end(), rng);
+    return edges;
+}
+
+// グラフをファイルストリームに出力する
+// ファイル名は prefix_num.in (ex: 1_tree_00.in)
+void output(int n, const vector<pair<int, int>> &edges, const string &prefix,
+            const int num) {
+    char name[100];
+    snprintf(name, sizeof(name), "%s/testcase/%s_%02d.in", root_path.c_str(),
+             prefix.c_str(), num);
+    ofstream ofs(name);
+    /* ここから書き換える */
+    ofs << n << " " << edges.size() << endl;
+    for (auto [u, v] : edges) ofs << u << " " << v << endl;
+    /* ここまで */
+    ofs.close();
+}
+
+int main(int argc, char *argv[]) {
+    init(argc, argv);
+    /* ここから書き換える */
+    for (int num = 0; num < 5; num++) {
+        int n = rand_int(1, 10);
+        output(n, random_tree(n), "1_tree", num);
+    }
+    for (int num = 0; num < 5; num++) {
+        int n = rand_int(2, 10);
+        int m = rand_int(n - 1, n * (n - 1) / 2);
+        output(n, random_connected_graph(n, m), "2_graph", num);
+    }
+    /* ここまで */
+}
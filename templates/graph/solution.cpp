@@ -0,0 +1,19 @@
+#include <iostream>
+#include <vector>
+using namespace std;
+
+int main() {
+    int n, m;
+    cin >> n >> m;
+    vector<vector<int>> graph(n + 1);
+    for (int i = 0; i < m; i++) {
+        int u, v;
+        cin >> u >> v;
+        graph[u].push_back(v);
+        graph[v].push_back(u);
+    }
+    /* ここに解答を書く */
+    long long ans = 0;
+    for (int v = 1; v <= n; v++) ans += (long long)graph[v].size() * v;
+    cout << ans << endl;
+}
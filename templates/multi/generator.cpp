@@ -0,0 +1,63 @@
+#include <fstream>
+#include <iostream>
+#include <random>
+#include <string>
+#include <vector>
+using namespace std;
+
+string root_path = "";
+mt19937_64 rng;
+
+// argv[1]: テストディレクトリへのパス
+// argv[2]: シード値 (cpstt stress 実行時のみ)
+void init(int argc, char *argv[]) {
+    root_path = std::string(argv[1]);
+    if (argc > 2) {
+        rng.seed((unsigned long long)(atoll(argv[2])));
+    } else {
+        rng.seed(random_device()());
+    }
+}
+
+// [lo, hi] の一様乱数
+long long rand_int(long long lo, long long hi) {
+    return uniform_int_distribution<long long>(lo, hi)(rng);
+}
+
+// 1つのケース
+struct Case {
+    vector<long long> a;
+};
+
+// 複数のケースを1つのテストケースとしてファイルストリームに出力する
+// ファイル名は prefix_num.in (ex: 1_random_00.in)
+void output(const vector<Case> &cases, const string &prefix, const int num) {
+    char name[100];
+    snprintf(name, sizeof(name), "%s/testcase/%s_%02d.in", root_path.c_str(),
+             prefix.c_str(), num);
+    ofstream ofs(name);
+    ofs << cases.size() << endl;
+    for (const Case &c : cases) {
+        /* ここから書き換える */
+        ofs << c.a.size() << endl;
+        for (size_t i = 0; i < c.a.size(); i++) {
+            ofs << c.a[i] << (i + 1 < c.a.size() ? " " : "\n");
+        }
+        /* ここまで */
+    }
+    ofs.close();
+}
+
+int main(int argc, char *argv[]) {
+    init(argc, argv);
+    /* ここから書き換える */
+    for (int num = 0; num < 10; num++) {
+        vector<Case> cases(rand_int(1, 100));
+        for (Case &c : cases) {
+            c.a.resize(rand_int(1, 10));
+            for (auto &x : c.a) x = rand_int(1, 100);
+        }
+        output(cases, "1_random", num);
+    }
+    /* ここまで */
+}
@@ -0,0 +1,21 @@
+#include <iostream>
+#include <vector>
+using namespace std;
+
+// 1つのケースを解く
+void solve() {
+    int n;
+    cin >> n;
+    vector<long long> a(n);
+    for (auto &x : a) cin >> x;
+    /* ここに解答を書く */
+    long long ans = 0;
+    for (auto x : a) ans += x;
+    cout << ans << endl;
+}
+
+int main() {
+    int t;
+    cin >> t;
+    while (t--) solve();
+}
@@ -0,0 +1,14 @@
+#include <iostream>
+#include <vector>
+using namespace std;
+
+int main() {
+    int n;
+    cin >> n;
+    vector<long long> a(n);
+    for (auto &x : a) cin >> x;
+    /* ここに解答を書く */
+    long long ans = 0;
+    for (auto x : a) ans += x;
+    cout << ans << endl;
+}
@@ -4,10 +4,14 @@ using namespace std;
 
 string root_path = "";
 
+// argv[1]: テストディレクトリへのパス
+// argv[2]: シード値 (cpstt stress 実行時のみ)
 void init(int argc, char *argv[]) {
-    srand((unsigned)(time(NULL)));
-    for (int i = 1; i < argc; i++) {
-        root_path += std::string(argv[i]);
+    root_path = std::string(argv[1]);
+    if (argc > 2) {
+        srand((unsigned)(atoll(argv[2])));
+    } else {
+        srand((unsigned)(time(NULL)));
     }
 }
 